@@ -0,0 +1,41 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use ratatui::style::{Color, Style};
+use regex::Regex;
+use tui_pattern_highlighter::{highlight_text, highlight_text_borrowed};
+
+const ITERATIONS: u32 = 200;
+const STYLE: Style = Style::new().bg(Color::Blue);
+const PATTERN: &str = r"@\w+";
+
+fn bench(name: &str, mut f: impl FnMut()) {
+    // warm up
+    f();
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{name:<24} {:>10.3?} / iter",
+        Duration::from_secs_f64(elapsed.as_secs_f64() / f64::from(ITERATIONS))
+    );
+}
+
+fn main() {
+    let text =
+        "Hello @Henry. Why are you named @nobody? Because yes, and you @John.\n".repeat(2_000);
+    let reg = Regex::new(PATTERN).unwrap();
+
+    bench("highlight_text", || {
+        black_box(highlight_text(black_box(text.clone()), PATTERN, STYLE));
+    });
+    bench("highlight_text_borrowed", || {
+        black_box(highlight_text_borrowed(black_box(&text), &reg, STYLE));
+    });
+}
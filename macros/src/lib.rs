@@ -0,0 +1,35 @@
+//! Procedural macros for `tui-pattern-highlighter`.
+//!
+//! Don't depend on this crate directly, enable the `macros` feature of `tui-pattern-highlighter`
+//! instead.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use regex::Regex;
+use syn::{parse_macro_input, LitStr};
+
+/// Validates a literal pattern at compile time and expands to a `&'static Regex` compiled on
+/// first use.
+///
+/// An invalid pattern fails the build instead of panicking at runtime.
+#[proc_macro]
+pub fn static_highlighter(input: TokenStream) -> TokenStream {
+    let pattern = parse_macro_input!(input as LitStr);
+
+    if let Err(err) = Regex::new(&pattern.value()) {
+        return syn::Error::new(pattern.span(), err)
+            .to_compile_error()
+            .into();
+    }
+
+    quote! {
+        {
+            static REGEX: ::std::sync::OnceLock<::tui_pattern_highlighter::__private::regex::Regex> =
+                ::std::sync::OnceLock::new();
+            REGEX.get_or_init(|| {
+                ::tui_pattern_highlighter::__private::regex::Regex::new(#pattern).unwrap()
+            })
+        }
+    }
+    .into()
+}
@@ -0,0 +1,181 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use ratatui::text::{Line, Text};
+
+use crate::Highlighter;
+
+/// Keeps the highlighted lines of a buffer around and, when the buffer changes, only
+/// re-highlights the lines that changed.
+///
+/// Every call to [`update`](Self::update) compares a hash of each new line with the hashes of
+/// the previous call. The lines common to the start and the end of both versions are reused,
+/// and only the lines in between are highlighted again, so editing, inserting or deleting lines
+/// in a large buffer only costs the lines that were touched. Lines are compared by their hash
+/// only, so in the astronomically unlikely case of a collision a stale line is reused.
+///
+/// The cost is one cached `Line` and one `u64` hash per line of the buffer. Changing the
+/// highlighter with [`set_highlighter`](Self::set_highlighter) clears the cache, and the next
+/// update highlights everything again.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{CachedHighlighter, Highlighter};
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let highlighter = Highlighter::new(r"@\w+", Style::new().bg(Color::Blue)).unwrap();
+/// let mut cached = CachedHighlighter::new(highlighter);
+///
+/// cached.update(&["Hi @buddy", "how are you?"]);
+/// // only the second line is highlighted again
+/// let lines = cached.update(&["Hi @buddy", "how are you @stranger?"]);
+///
+/// assert_eq!(
+///     lines[1],
+///     Line::from(vec![
+///         Span::from("how are you "),
+///         Span::from("@stranger").style(Style::new().bg(Color::Blue)),
+///         Span::from("?"),
+///     ])
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct CachedHighlighter {
+    highlighter: Highlighter,
+    hashes: Vec<u64>,
+    lines: Vec<Line<'static>>,
+}
+
+impl CachedHighlighter {
+    /// Creates a new `CachedHighlighter` with an empty cache.
+    pub fn new(highlighter: Highlighter) -> Self {
+        Self {
+            highlighter,
+            hashes: Vec::new(),
+            lines: Vec::new(),
+        }
+    }
+
+    /// Returns the highlighter used for the lines.
+    pub fn highlighter(&self) -> &Highlighter {
+        &self.highlighter
+    }
+
+    /// Replaces the highlighter and clears the cache, since none of the cached lines can be
+    /// reused with another pattern or style.
+    pub fn set_highlighter(&mut self, highlighter: Highlighter) {
+        self.highlighter = highlighter;
+        self.hashes.clear();
+        self.lines.clear();
+    }
+
+    /// Brings the cache up to date with `lines`, highlighting only the lines that changed since
+    /// the last update, and returns the highlighted lines.
+    pub fn update(&mut self, lines: &[&str]) -> &[Line<'static>] {
+        let hashes = lines.iter().map(|line| hash(line)).collect::<Vec<_>>();
+
+        let prefix = self
+            .hashes
+            .iter()
+            .zip(&hashes)
+            .take_while(|(old, new)| old == new)
+            .count();
+        let suffix = self.hashes[prefix..]
+            .iter()
+            .rev()
+            .zip(hashes[prefix..].iter().rev())
+            .take_while(|(old, new)| old == new)
+            .count();
+
+        let old_end = self.hashes.len() - suffix;
+        let new_end = hashes.len() - suffix;
+        self.lines.splice(
+            prefix..old_end,
+            lines[prefix..new_end]
+                .iter()
+                .map(|line| self.highlighter.line(line)),
+        );
+        self.hashes = hashes;
+
+        &self.lines
+    }
+
+    /// Returns the highlighted lines of the last update.
+    pub fn lines(&self) -> &[Line<'static>] {
+        &self.lines
+    }
+
+    /// Returns the highlighted lines of the last update as `Text`.
+    pub fn text(&self) -> Text<'static> {
+        Text::from(self.lines.clone())
+    }
+}
+
+fn hash(line: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    line.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{
+        style::{Color, Style},
+        text::Span,
+    };
+
+    const STYLE: Style = Style::new().bg(Color::Blue);
+
+    #[test]
+    fn cached_highlighter_test() {
+        let highlighter = Highlighter::new(r"@\w+", STYLE).unwrap();
+        let mut cached = CachedHighlighter::new(highlighter.clone());
+        let full = |lines: &[&str]| highlighter.highlight_text(lines.join("\n")).lines;
+
+        let versions: [&[&str]; 6] = [
+            &["@a", "b", "@c d", "e"],
+            &["@a", "b @x", "@c d", "e"],
+            &["@a", "new", "b @x", "@c d", "e"],
+            &["@a", "e"],
+            &["@a", "e", "e", "@f"],
+            &[],
+        ];
+        for lines in versions {
+            assert_eq!(cached.update(lines), full(lines));
+        }
+        assert_eq!(cached.text(), Text::default());
+    }
+
+    #[test]
+    fn cached_highlighter_reuse_test() {
+        let highlighter = Highlighter::new(r"@\w+", STYLE).unwrap();
+        let mut cached = CachedHighlighter::new(highlighter);
+        cached.update(&["@a", "b", "@c"]);
+
+        // mark the cached lines, so the ones that get highlighted again lose the mark
+        for line in &mut cached.lines {
+            line.style = Style::new().fg(Color::Red);
+        }
+        let lines = cached.update(&["@a", "inserted", "b!", "@c"]);
+
+        let marked = lines
+            .iter()
+            .map(|line| line.style == Style::new().fg(Color::Red))
+            .collect::<Vec<_>>();
+        assert_eq!(marked, vec![true, false, false, true]);
+
+        cached.set_highlighter(Highlighter::new(r"\w", STYLE).unwrap());
+        assert!(cached.lines().is_empty());
+        assert_eq!(
+            cached.update(&["@a"]),
+            vec![Line::from(vec![
+                Span::from("@"),
+                Span::from("a").style(STYLE),
+            ])]
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use ratatui::style::{Color, Style};
+
+/// How many colors a terminal can display.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorSupport {
+    /// 24-bit RGB colors.
+    #[default]
+    TrueColor,
+    /// The 256 colors of the xterm palette.
+    Ansi256,
+    /// The 16 basic ANSI colors.
+    Ansi16,
+}
+
+// the xterm defaults for the 16 basic colors
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Downgrades `color` to the nearest color a terminal with the given `support` can display.
+///
+/// The nearest color is the one with the smallest Euclidean distance in RGB space, using the
+/// xterm default palette as the RGB values of indexed and named colors.
+///
+/// * With [`ColorSupport::TrueColor`] every color is returned as it is.
+/// * With [`ColorSupport::Ansi256`] `Color::Rgb` becomes the nearest `Color::Indexed` from the
+///   6×6×6 color cube (16-231) or the grayscale ramp (232-255). The 16 basic colors aren't
+///   candidates, since their actual values vary the most between terminal themes.
+/// * With [`ColorSupport::Ansi16`] both `Color::Rgb` and `Color::Indexed` become the nearest of
+///   the 16 named colors.
+///
+/// `Color::Reset` and the named colors are never changed.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{degrade_color, ColorSupport};
+/// use ratatui::style::Color;
+///
+/// assert_eq!(degrade_color(Color::Rgb(255, 0, 0), ColorSupport::Ansi256), Color::Indexed(196));
+/// assert_eq!(degrade_color(Color::Rgb(250, 10, 10), ColorSupport::Ansi16), Color::LightRed);
+/// assert_eq!(degrade_color(Color::Indexed(22), ColorSupport::Ansi16), Color::Black);
+/// ```
+pub fn degrade_color(color: Color, support: ColorSupport) -> Color {
+    match (support, color) {
+        (ColorSupport::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_indexed((r, g, b))),
+        (ColorSupport::Ansi16, Color::Rgb(r, g, b)) => nearest_named((r, g, b)),
+        (ColorSupport::Ansi16, Color::Indexed(i)) if i < 16 => ANSI16[usize::from(i)].0,
+        (ColorSupport::Ansi16, Color::Indexed(i)) => nearest_named(indexed_rgb(i)),
+        _ => color,
+    }
+}
+
+/// Downgrades every color of `style` with [`degrade_color`].
+pub fn degrade_style(style: Style, support: ColorSupport) -> Style {
+    Style {
+        fg: style.fg.map(|color| degrade_color(color, support)),
+        bg: style.bg.map(|color| degrade_color(color, support)),
+        underline_color: style
+            .underline_color
+            .map(|color| degrade_color(color, support)),
+        ..style
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| u32::from(x.abs_diff(y)).pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn nearest_named(rgb: (u8, u8, u8)) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, value)| distance(rgb, *value))
+        .unwrap()
+        .0
+}
+
+fn nearest_indexed(rgb: (u8, u8, u8)) -> u8 {
+    (16..=255)
+        .min_by_key(|&i| distance(rgb, indexed_rgb(i)))
+        .unwrap()
+}
+
+fn indexed_rgb(i: u8) -> (u8, u8, u8) {
+    match i {
+        0..=15 => ANSI16[usize::from(i)].1,
+        16..=231 => {
+            let i = i - 16;
+            (
+                CUBE_LEVELS[usize::from(i / 36)],
+                CUBE_LEVELS[usize::from(i / 6 % 6)],
+                CUBE_LEVELS[usize::from(i % 6)],
+            )
+        }
+        232..=255 => {
+            let level = 8 + 10 * (i - 232);
+            (level, level, level)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Modifier;
+
+    #[test]
+    fn degrading_color_test() {
+        let cases = [
+            (Color::Rgb(0, 0, 0), Color::Indexed(16), Color::Black),
+            (Color::Rgb(255, 255, 255), Color::Indexed(231), Color::White),
+            (Color::Rgb(0, 0, 255), Color::Indexed(21), Color::Blue),
+            (
+                Color::Rgb(128, 128, 128),
+                Color::Indexed(244),
+                Color::DarkGray,
+            ),
+            (
+                Color::Rgb(95, 135, 175),
+                Color::Indexed(67),
+                Color::DarkGray,
+            ),
+            (Color::Rgb(200, 190, 10), Color::Indexed(178), Color::Yellow),
+        ];
+
+        for (color, ansi256, ansi16) in cases {
+            assert_eq!(degrade_color(color, ColorSupport::TrueColor), color);
+            assert_eq!(degrade_color(color, ColorSupport::Ansi256), ansi256);
+            assert_eq!(degrade_color(color, ColorSupport::Ansi16), ansi16);
+        }
+
+        assert_eq!(
+            degrade_color(Color::Indexed(9), ColorSupport::Ansi16),
+            Color::LightRed
+        );
+        assert_eq!(
+            degrade_color(Color::Indexed(250), ColorSupport::Ansi16),
+            Color::Gray
+        );
+        assert_eq!(
+            degrade_color(Color::Indexed(250), ColorSupport::Ansi256),
+            Color::Indexed(250)
+        );
+        assert_eq!(
+            degrade_color(Color::Reset, ColorSupport::Ansi16),
+            Color::Reset
+        );
+        assert_eq!(
+            degrade_color(Color::Cyan, ColorSupport::Ansi16),
+            Color::Cyan
+        );
+    }
+
+    #[test]
+    fn degrading_style_test() {
+        let style = Style::new()
+            .fg(Color::Rgb(255, 0, 0))
+            .bg(Color::Rgb(0, 0, 0))
+            .underline_color(Color::Indexed(21))
+            .add_modifier(Modifier::BOLD);
+
+        assert_eq!(
+            degrade_style(style, ColorSupport::Ansi16),
+            Style::new()
+                .fg(Color::LightRed)
+                .bg(Color::Black)
+                .underline_color(Color::Blue)
+                .add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(
+            degrade_style(Style::new(), ColorSupport::Ansi16),
+            Style::new()
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+
+/// Creates a `Line` from `candidate` and adds `highlight_style` to the part of it that
+/// `typed` is a prefix of, for completion menus.
+///
+/// The prefix is compared case-insensitively, char by char using simple lowercase folding.
+/// When `typed` is empty, longer than `candidate` or not a prefix of it, the whole candidate
+/// is returned as a single plain `Span`.
+///
+/// # Arguments
+///
+/// * `candidate` - A string slice that holds the completion candidate.
+/// * `typed` - A string slice that holds what the user typed so far.
+/// * `highlight_style` - The style to be applied to the matching prefix.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_prefix;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("Hel").style(Style::new().bg(Color::Blue)),
+///     Span::from("lo"),
+/// ]);
+///
+/// assert_eq!(highlight_prefix("Hello", "hEL", Style::new().bg(Color::Blue)), expected_line);
+/// assert_eq!(highlight_prefix("Hello", "lo", Style::new()), Line::from(vec![Span::from("Hello")]));
+/// ```
+pub fn highlight_prefix<'a>(candidate: &str, typed: &str, highlight_style: Style) -> Line<'a> {
+    let mut highlighted_line = Line::default();
+    let len = folded_prefix_len(candidate, typed).unwrap_or(0);
+
+    if len > 0 {
+        highlighted_line.push_span(Span::from(candidate[..len].to_string()).style(highlight_style));
+    }
+    if candidate.len() > len {
+        highlighted_line.push_span(Span::from(candidate[len..].to_string()));
+    }
+
+    highlighted_line
+}
+
+/// Creates a `Line` from `candidate` and adds `highlight_style` to the characters that match
+/// `query` as a subsequence, like fuzzy finders do. Returns `None` if `query` isn't a
+/// subsequence of `candidate`.
+///
+/// Characters are compared case-insensitively, using simple lowercase folding. The match is
+/// greedy and leftmost: every character of `query` is matched with the first matching
+/// character of `candidate` after the previous one, without any scoring, so e.g. `"ab"` in
+/// `"a_xab"` highlights the first `a`, not the adjacent `ab`. Consecutive matched characters
+/// share one `Span`, and an empty `query` matches with nothing highlighted.
+///
+/// # Arguments
+///
+/// * `candidate` - A string slice that holds the candidate to be highlighted.
+/// * `query` - A string slice that holds the fuzzy query.
+/// * `highlight_style` - The style to be applied to the matched characters.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_fuzzy;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("sr").style(Style::new().bg(Color::Blue)),
+///     Span::from("c/"),
+///     Span::from("l").style(Style::new().bg(Color::Blue)),
+///     Span::from("ib.rs"),
+/// ]);
+///
+/// assert_eq!(highlight_fuzzy("src/lib.rs", "SRL", Style::new().bg(Color::Blue)), Some(expected_line));
+/// assert_eq!(highlight_fuzzy("src/lib.rs", "rsl", Style::new()), None);
+/// ```
+pub fn highlight_fuzzy<'a>(
+    candidate: &str,
+    query: &str,
+    highlight_style: Style,
+) -> Option<Line<'a>> {
+    let mut highlighted_line = Line::default();
+    let mut query_chars = query.chars().peekable();
+    // where the current run of plain or matched characters starts, and which one it is
+    let mut run_start = 0;
+    let mut run_matched = false;
+
+    for (i, c) in candidate.char_indices() {
+        let matched = query_chars.next_if(|&q| chars_eq_folded(c, q)).is_some();
+
+        if matched != run_matched {
+            if i > run_start {
+                let span = Span::from(candidate[run_start..i].to_string());
+                highlighted_line.push_span(if run_matched {
+                    span.style(highlight_style)
+                } else {
+                    span
+                });
+            }
+            run_start = i;
+            run_matched = matched;
+        }
+    }
+
+    if query_chars.next().is_some() {
+        return None;
+    }
+
+    if candidate.len() > run_start {
+        let span = Span::from(candidate[run_start..].to_string());
+        highlighted_line.push_span(if run_matched {
+            span.style(highlight_style)
+        } else {
+            span
+        });
+    }
+
+    Some(highlighted_line)
+}
+
+fn folded_prefix_len(haystack: &str, prefix: &str) -> Option<usize> {
+    let mut haystack_chars = haystack.char_indices();
+
+    for p in prefix.chars() {
+        let (_, h) = haystack_chars.next()?;
+        if !chars_eq_folded(h, p) {
+            return None;
+        }
+    }
+
+    Some(haystack_chars.next().map_or(haystack.len(), |(i, _)| i))
+}
+
+fn chars_eq_folded(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    const STYLE: Style = Style::new().bg(Color::Blue);
+
+    #[test]
+    fn highlighting_prefix_test() {
+        assert_eq!(
+            highlight_prefix("Żółw", "żó", STYLE),
+            Line::from(vec![Span::from("Żó").style(STYLE), Span::from("łw"),])
+        );
+        assert_eq!(
+            highlight_prefix("abc", "ABC", STYLE),
+            Line::from(vec![Span::from("abc").style(STYLE)])
+        );
+        for typed in ["", "abcd", "b"] {
+            assert_eq!(
+                highlight_prefix("abc", typed, STYLE),
+                Line::from(vec![Span::from("abc")])
+            );
+        }
+        assert_eq!(highlight_prefix("", "a", STYLE), Line::default());
+    }
+
+    #[test]
+    fn highlighting_fuzzy_test() {
+        assert_eq!(
+            highlight_fuzzy("a_xab", "AB", STYLE),
+            Some(Line::from(vec![
+                Span::from("a").style(STYLE),
+                Span::from("_xa"),
+                Span::from("b").style(STYLE),
+            ]))
+        );
+        assert_eq!(
+            highlight_fuzzy("Żółw", "żółw", STYLE),
+            Some(Line::from(vec![Span::from("Żółw").style(STYLE)]))
+        );
+        assert_eq!(
+            highlight_fuzzy("abc", "", STYLE),
+            Some(Line::from(vec![Span::from("abc")]))
+        );
+        assert_eq!(highlight_fuzzy("", "", STYLE), Some(Line::default()));
+        assert_eq!(highlight_fuzzy("abc", "abcd", STYLE), None);
+        assert_eq!(highlight_fuzzy("abc", "ca", STYLE), None);
+    }
+}
@@ -0,0 +1,162 @@
+#[cfg(feature = "regex")]
+use ratatui::{style::Style, text::Text};
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+#[cfg(feature = "regex")]
+use crate::highlight_text_with;
+
+/// Placeholder glyphs used when turning raw bytes into displayable text.
+///
+/// The defaults are `'�'` for invalid UTF-8, `'→'` for tabs and `'^'` as the prefix of the
+/// caret notation of control characters (e.g. `\x01` is shown as `^A`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Glyphs {
+    /// Replaces each invalid UTF-8 sequence.
+    pub replacement: char,
+    /// Replaces each `'\t'`.
+    pub tab: char,
+    /// Prefixes the caret notation of ASCII control characters.
+    pub control_prefix: char,
+}
+
+impl Default for Glyphs {
+    fn default() -> Self {
+        Self {
+            replacement: char::REPLACEMENT_CHARACTER,
+            tab: '→',
+            control_prefix: '^',
+        }
+    }
+}
+
+/// Decodes `bytes` into a displayable `String`, substituting `glyphs` for anything that can't
+/// be shown as is.
+///
+/// Every invalid UTF-8 sequence collapses into a single `replacement` glyph, following the same
+/// rules as [`String::from_utf8_lossy`]. Tabs become the `tab` glyph, and all other ASCII control
+/// characters except `'\n'` are written in caret notation (`^@` to `^_`, and `^?` for DEL).
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{sanitize_bytes, Glyphs};
+///
+/// assert_eq!(sanitize_bytes(b"a\tb\x01\xff\n", Glyphs::default()), "a→b^A�\n");
+/// ```
+pub fn sanitize_bytes(bytes: &[u8], glyphs: Glyphs) -> String {
+    let mut sanitized = String::with_capacity(bytes.len());
+
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '\n' => sanitized.push(c),
+                '\t' => sanitized.push(glyphs.tab),
+                c if c.is_ascii_control() => {
+                    sanitized.push(glyphs.control_prefix);
+                    sanitized.push((c as u8 ^ 0x40) as char);
+                }
+                c => sanitized.push(c),
+            }
+        }
+
+        if !chunk.invalid().is_empty() {
+            sanitized.push(glyphs.replacement);
+        }
+    }
+
+    sanitized
+}
+
+/// Creates `Text` from raw `bytes` like [`highlight_text`](crate::highlight_text), after
+/// decoding them with [`sanitize_bytes`].
+///
+/// The pattern is matched against the sanitized text, so it sees the glyphs rather than the
+/// original bytes.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to be decoded and highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+/// * `glyphs` - The placeholder glyphs to use while decoding.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{highlight_bytes, Glyphs};
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let glyphs = Glyphs { replacement: '?', ..Glyphs::default() };
+///
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("Hi "),
+///     Span::from("@bud?y").style(Style::new().bg(Color::Blue)),
+/// ])]);
+///
+/// assert_eq!(
+///     highlight_bytes(b"Hi @bud\xffy", r"@\S+", Style::new().bg(Color::Blue), glyphs),
+///     expected_text
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_bytes<'a>(
+    bytes: &[u8],
+    pattern: impl AsRef<str>,
+    highlight_style: Style,
+    glyphs: Glyphs,
+) -> Text<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+    highlight_text_with(&sanitize_bytes(bytes, glyphs), &reg, highlight_style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "regex")]
+    use crate::highlight_text;
+    #[cfg(feature = "regex")]
+    use ratatui::style::Color;
+
+    #[cfg(feature = "regex")]
+    const STYLE: Style = Style::new().bg(Color::Blue);
+
+    #[test]
+    fn sanitizing_bytes_test() {
+        let bytes = b"\x00tab\there\x1b[0m\x7f\r\n\xf0\x9f\x98\xc3(ok)\xe2\x82";
+
+        assert_eq!(
+            sanitize_bytes(bytes, Glyphs::default()),
+            "^@tab→here^[[0m^?^M\n��(ok)�"
+        );
+        assert_eq!(
+            sanitize_bytes(
+                bytes,
+                Glyphs {
+                    replacement: '#',
+                    tab: ' ',
+                    control_prefix: '\\',
+                }
+            ),
+            "\\@tab here\\[[0m\\?\\M\n##(ok)#"
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn highlighting_bytes_test() {
+        let bytes = b"@a\t@b\n@\xffc";
+
+        assert_eq!(
+            highlight_bytes(bytes, r"@\w+", STYLE, Glyphs::default()),
+            highlight_text(String::from("@a→@b\n@�c"), r"@\w+", STYLE)
+        );
+    }
+}
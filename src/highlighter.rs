@@ -0,0 +1,1448 @@
+use std::{borrow::Cow, ops::Range};
+
+use ratatui::{
+    style::Style,
+    text::{Line, Span, Text},
+};
+use regex::{Match, Regex, RegexBuilder};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{
+    degrade_style,
+    segments::fill_gaps,
+    spans::{into_owned, MatchSpans},
+    ColorSupport, NewlineOptions, QuoteFilter,
+};
+
+/// The marker appended to lines cut short by [`Highlighter::max_line_bytes`].
+pub const TRUNCATION_MARKER: &str = "[…]";
+
+/// Flags controlling how a pattern is compiled, as an alternative to embedding inline flags
+/// like `(?im)` in the pattern.
+///
+/// Each flag maps to the [`RegexBuilder`] option of the same name, and the defaults are the
+/// ones of the `regex` crate: everything is off except for `unicode`. Inline flags in the
+/// pattern still take precedence for the part of the pattern they apply to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct RegexFlags {
+    /// Matches letters regardless of their case (`i`).
+    pub case_insensitive: bool,
+    /// Makes `^` and `$` match at the start and end of every line (`m`).
+    pub multi_line: bool,
+    /// Makes `.` match `'\n'` too (`s`).
+    pub dot_matches_new_line: bool,
+    /// Makes `a*` lazy and `a*?` greedy (`U`).
+    pub swap_greed: bool,
+    /// Ignores whitespace in the pattern and allows `#` comments (`x`).
+    pub ignore_whitespace: bool,
+    /// Makes character classes like `\w` Unicode-aware (`u`).
+    pub unicode: bool,
+}
+
+impl Default for RegexFlags {
+    fn default() -> Self {
+        Self {
+            case_insensitive: false,
+            multi_line: false,
+            dot_matches_new_line: false,
+            swap_greed: false,
+            ignore_whitespace: false,
+            unicode: true,
+        }
+    }
+}
+
+impl RegexFlags {
+    pub(crate) fn build(&self, pattern: &str) -> Result<Regex, regex::Error> {
+        RegexBuilder::new(pattern)
+            .case_insensitive(self.case_insensitive)
+            .multi_line(self.multi_line)
+            .dot_matches_new_line(self.dot_matches_new_line)
+            .swap_greed(self.swap_greed)
+            .ignore_whitespace(self.ignore_whitespace)
+            .unicode(self.unicode)
+            .build()
+    }
+}
+
+/// A highlighted `Line`, along with whether anything in it was highlighted.
+///
+/// This is returned by [`Highlighter::highlight_line_checked`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Highlighted<'a> {
+    /// The highlighted line.
+    pub line: Line<'a>,
+    /// Whether at least one match was styled. Zero-width matches have nothing to style, so they
+    /// don't count, and neither do matches cut off by [`Highlighter::max_line_bytes`] or skipped
+    /// by [`Highlighter::quotes`].
+    pub matched: bool,
+}
+
+/// A pattern compiled once and reused to highlight many lines.
+///
+/// Unlike the free functions, a `Highlighter` compiles its regular expression only once, and
+/// reports an invalid pattern as an error instead of panicking.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::Highlighter;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let highlighter = Highlighter::new(r"@\w+", Style::new().bg(Color::Blue)).unwrap();
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("Hi "),
+///     Span::from("@buddy").style(Style::new().bg(Color::Blue)),
+/// ]);
+///
+/// assert_eq!(highlighter.highlight_line(String::from("Hi @buddy")), expected_line);
+/// ```
+///
+/// Cloning a `Highlighter` is cheap: the compiled regular expression is shared between the
+/// clones rather than compiled again, so it's fine to keep snapshots of it, e.g. for undoing
+/// changes to the highlight settings.
+#[derive(Debug, Clone)]
+pub struct Highlighter {
+    regex: Regex,
+    style: Style,
+    flags: RegexFlags,
+    max_line_bytes: Option<usize>,
+    truncation_style: Style,
+    base_style: Style,
+    tab_width: Option<usize>,
+    color_support: ColorSupport,
+    capacity_hint: usize,
+    max_span_width: Option<usize>,
+    quotes: Option<QuoteFilter>,
+    newlines: NewlineOptions,
+}
+
+impl Highlighter {
+    /// Compiles `pattern` into a new `Highlighter` that applies `highlight_style` to matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the provided pattern is an invalid regular expression.
+    pub fn new(pattern: impl AsRef<str>, highlight_style: Style) -> Result<Self, regex::Error> {
+        Self::with_flags(pattern, highlight_style, RegexFlags::default())
+    }
+
+    /// Compiles `pattern` with the given `flags` into a new `Highlighter` that applies
+    /// `highlight_style` to matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the provided pattern is an invalid regular expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::{Highlighter, RegexFlags};
+    /// use ratatui::{
+    ///     style::{Color, Style},
+    ///     text::{Line, Span},
+    /// };
+    ///
+    /// let flags = RegexFlags { case_insensitive: true, ..RegexFlags::default() };
+    /// let highlighter = Highlighter::with_flags("todo", Style::new().bg(Color::Blue), flags).unwrap();
+    ///
+    /// let expected_line = Line::from(vec![
+    ///     Span::from("TODO").style(Style::new().bg(Color::Blue)),
+    ///     Span::from(": fix"),
+    /// ]);
+    ///
+    /// assert_eq!(highlighter.highlight_line(String::from("TODO: fix")), expected_line);
+    /// ```
+    pub fn with_flags(
+        pattern: impl AsRef<str>,
+        highlight_style: Style,
+        flags: RegexFlags,
+    ) -> Result<Self, regex::Error> {
+        Ok(Self {
+            regex: flags.build(pattern.as_ref())?,
+            style: highlight_style,
+            flags,
+            max_line_bytes: None,
+            truncation_style: Style::default(),
+            base_style: Style::default(),
+            tab_width: None,
+            color_support: ColorSupport::default(),
+            capacity_hint: 0,
+            max_span_width: None,
+            quotes: None,
+            newlines: NewlineOptions::default(),
+        })
+    }
+
+    /// Recompiles the pattern with Unicode support turned on or off. It's on by default.
+    ///
+    /// Turning it off makes matching faster, which pays off for high-throughput ASCII data like
+    /// logs, but it also changes the meaning of classes like `\w`, `\d`, `\s` and `\b` to
+    /// their ASCII-only versions. For example, `\b\w+\b` finds `café` in `"café"` with
+    /// Unicode support, but only `caf` without it, since `é` is no longer a word character.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern can't be compiled without Unicode support, which is the
+    /// case for patterns that could match invalid UTF-8, like `.`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::Highlighter;
+    /// use ratatui::style::Style;
+    ///
+    /// let highlighter = Highlighter::new(r"\w+", Style::new()).unwrap();
+    /// assert_eq!(highlighter.highlight_line(String::from("café")).spans.len(), 1);
+    ///
+    /// let highlighter = highlighter.unicode(false).unwrap();
+    /// assert_eq!(highlighter.highlight_line(String::from("café")).spans.len(), 2);
+    /// ```
+    pub fn unicode(self, unicode: bool) -> Result<Self, regex::Error> {
+        let flags = RegexFlags {
+            unicode,
+            ..self.flags
+        };
+
+        Ok(Self {
+            regex: flags.build(self.regex.as_str())?,
+            flags,
+            ..self
+        })
+    }
+
+    /// Limits the length of each line to `max_line_bytes`, to protect against pathologically
+    /// long lines. There's no limit by default.
+    ///
+    /// A longer line is cut at the last char boundary within the limit, and gets a
+    /// [`TRUNCATION_MARKER`] `Span` styled with the [truncation style](Self::truncation_style)
+    /// appended. Lines are truncated before matching, so matches in the cut off part are simply
+    /// not found, and a match crossing the cut is only highlighted up to it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::Highlighter;
+    /// use ratatui::{
+    ///     style::{Color, Style},
+    ///     text::{Line, Span},
+    /// };
+    ///
+    /// let highlighter = Highlighter::new(r"@\w+", Style::new().bg(Color::Blue))
+    ///     .unwrap()
+    ///     .max_line_bytes(Some(6));
+    ///
+    /// let expected_line = Line::from(vec![
+    ///     Span::from("Hi "),
+    ///     Span::from("@bu").style(Style::new().bg(Color::Blue)),
+    ///     Span::from("[…]"),
+    /// ]);
+    ///
+    /// assert_eq!(highlighter.highlight_line(String::from("Hi @buddy")), expected_line);
+    /// ```
+    pub fn max_line_bytes(self, max_line_bytes: Option<usize>) -> Self {
+        Self {
+            max_line_bytes,
+            ..self
+        }
+    }
+
+    /// Sets the style of the [`TRUNCATION_MARKER`] appended to truncated lines.
+    pub fn truncation_style(self, truncation_style: Style) -> Self {
+        Self {
+            truncation_style,
+            ..self
+        }
+    }
+
+    /// Styles the text around the matches with `base_style`, and patches the highlight style on
+    /// top of it for the matches, e.g. to give a whole paragraph a consistent background. The
+    /// base style is the default one by default, which leaves unmatched text unstyled.
+    ///
+    /// The [`TRUNCATION_MARKER`] gets the truncation style patched on top of the base style
+    /// too. [`highlight_span`](Self::highlight_span) and
+    /// [`highlight_styled_line`](Self::highlight_styled_line) keep the styles of the given
+    /// `Spans` instead, and ignore the base style.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::Highlighter;
+    /// use ratatui::{
+    ///     style::{Color, Style},
+    ///     text::{Line, Span},
+    /// };
+    ///
+    /// let highlighter = Highlighter::new(r"@\w+", Style::new().fg(Color::Blue))
+    ///     .unwrap()
+    ///     .base_style(Style::new().fg(Color::DarkGray).bg(Color::Black));
+    ///
+    /// let expected_line = Line::from(vec![
+    ///     Span::from("Hi ").style(Style::new().fg(Color::DarkGray).bg(Color::Black)),
+    ///     Span::from("@buddy").style(Style::new().fg(Color::Blue).bg(Color::Black)),
+    /// ]);
+    ///
+    /// assert_eq!(highlighter.highlight_line(String::from("Hi @buddy")), expected_line);
+    /// ```
+    pub fn base_style(self, base_style: Style) -> Self {
+        Self { base_style, ..self }
+    }
+
+    /// Creates a `Line` like [`highlight_line`](crate::highlight_line).
+    pub fn highlight_line<'a>(&self, line: String) -> Line<'a> {
+        self.line(&line)
+    }
+
+    /// Creates a `Line` like [`highlight_line`](Self::highlight_line), and reports whether
+    /// anything was highlighted, without searching the line a second time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::Highlighter;
+    /// use ratatui::style::{Color, Style};
+    ///
+    /// let highlighter = Highlighter::new(r"@\w+", Style::new().bg(Color::Blue)).unwrap();
+    ///
+    /// let highlighted = highlighter.highlight_line_checked(String::from("Hi @buddy"));
+    /// assert!(highlighted.matched);
+    /// assert_eq!(highlighted.line, highlighter.highlight_line(String::from("Hi @buddy")));
+    ///
+    /// assert!(!highlighter.highlight_line_checked(String::from("Hi buddy")).matched);
+    /// ```
+    pub fn highlight_line_checked<'a>(&self, line: String) -> Highlighted<'a> {
+        self.checked_line(&line)
+    }
+
+    /// Creates `Text` like [`highlight_text`](crate::highlight_text), keeping the line endings
+    /// if [`newlines`](Self::newlines) says so.
+    pub fn highlight_text<'a>(&self, text: String) -> Text<'a> {
+        if !self.newlines.keep_newlines {
+            return text
+                .split_terminator('\n')
+                .map(|line| self.line(line))
+                .collect();
+        }
+
+        text.split_inclusive('\n')
+            .map(|line| {
+                let Some(line) = line.strip_suffix('\n') else {
+                    return self.line(line);
+                };
+                let (line, ending) = match line.strip_suffix('\r') {
+                    Some(line) => (line, "\r\n"),
+                    None => (line, "\n"),
+                };
+
+                let ending = match self.newlines.glyph {
+                    Some(glyph) => glyph.to_string(),
+                    None => ending.to_string(),
+                };
+                let style = degrade_style(
+                    self.base_style.patch(self.newlines.style),
+                    self.color_support,
+                );
+
+                let mut highlighted_line = self.line(line);
+                highlighted_line.push_span(Span::from(ending).style(style));
+                highlighted_line
+            })
+            .collect()
+    }
+
+    /// Highlights the content of a single `Span`, splitting it into plain and highlighted
+    /// pieces.
+    ///
+    /// The plain pieces keep the style of `span`, and the highlighted ones get the highlight
+    /// style patched on top of it. Content borrowed by `span` stays borrowed by the pieces.
+    ///
+    /// This is meant for incremental editing: instead of rebuilding a whole `Line` after every
+    /// keystroke, re-highlight just the edited `Span` and splice the pieces back in its place,
+    /// e.g. with `line.spans.splice(i..=i, highlighter.highlight_span(span))`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::Highlighter;
+    /// use ratatui::{
+    ///     style::{Color, Style},
+    ///     text::{Line, Span},
+    /// };
+    ///
+    /// let highlighter = Highlighter::new(r"@\w+", Style::new().bg(Color::Blue)).unwrap();
+    /// let mut line = Line::from(vec![Span::from("> "), Span::from("Hi @buddy").style(Color::Red)]);
+    ///
+    /// let span = line.spans[1].clone();
+    /// line.spans.splice(1..=1, highlighter.highlight_span(span));
+    ///
+    /// let expected_line = Line::from(vec![
+    ///     Span::from("> "),
+    ///     Span::from("Hi ").style(Style::new().fg(Color::Red)),
+    ///     Span::from("@buddy").style(Style::new().fg(Color::Red).bg(Color::Blue)),
+    /// ]);
+    ///
+    /// assert_eq!(line, expected_line);
+    /// ```
+    pub fn highlight_span<'a>(&self, mut span: Span<'a>) -> Vec<Span<'a>> {
+        // every piece is a clone of `span` with its own content and style, so nothing else a
+        // `Span` carries gets lost
+        let content = std::mem::take(&mut span.content);
+        let piece = |range: Range<usize>, style: Style| {
+            let mut piece = span.clone();
+            piece.content = match &content {
+                Cow::Borrowed(content) => Cow::Borrowed(&content[range]),
+                Cow::Owned(content) => Cow::Owned(content[range].to_string()),
+            };
+            piece.style = degrade_style(style, self.color_support);
+            piece
+        };
+
+        fill_gaps(content.len(), self.regex.find_iter(&content))
+            .map(|(range, m)| match m {
+                Some(_) => piece(range, span.style.patch(self.style)),
+                None => piece(range, span.style),
+            })
+            .collect()
+    }
+
+    /// Re-highlights an already styled `line`, splitting each of its `Spans` with
+    /// [`highlight_span`](Self::highlight_span).
+    ///
+    /// The existing styles are kept, with the highlight style patched on top of them for
+    /// matches, and so are the style and alignment of the `Line` itself. Matches are searched
+    /// for in each `Span` separately, so a match can't span two `Spans`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::Highlighter;
+    /// use ratatui::{
+    ///     style::{Color, Style},
+    ///     text::{Line, Span},
+    /// };
+    ///
+    /// let highlighter = Highlighter::new(r"@\w+", Style::new().bg(Color::Blue)).unwrap();
+    /// let line = Line::from(vec![Span::from("> "), Span::from("Hi @buddy").style(Color::Red)])
+    ///     .centered();
+    ///
+    /// let expected_line = Line::from(vec![
+    ///     Span::from("> "),
+    ///     Span::from("Hi ").style(Style::new().fg(Color::Red)),
+    ///     Span::from("@buddy").style(Style::new().fg(Color::Red).bg(Color::Blue)),
+    /// ])
+    /// .centered();
+    ///
+    /// assert_eq!(highlighter.highlight_styled_line(line), expected_line);
+    /// ```
+    pub fn highlight_styled_line<'a>(&self, line: Line<'a>) -> Line<'a> {
+        Line {
+            spans: line
+                .spans
+                .into_iter()
+                .flat_map(|span| self.highlight_span(span))
+                .collect(),
+            ..line
+        }
+    }
+
+    /// Expands tabs into spaces, up to the next multiple of `tab_width` columns. Tabs are kept
+    /// as they are by default, and a `tab_width` of zero removes them.
+    ///
+    /// The pattern is matched against the original text, before the expansion, so the spaces
+    /// inserted for a tab always belong to the `Span` the tab was in: a tab next to a match
+    /// becomes plain spaces, and only a tab that is itself part of a match becomes highlighted
+    /// spaces. Columns are counted in display width, so wide chars like CJK ideographs count
+    /// as two.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::Highlighter;
+    /// use ratatui::{
+    ///     style::{Color, Style},
+    ///     text::{Line, Span},
+    /// };
+    ///
+    /// let highlighter = Highlighter::new(r"@\w+", Style::new().bg(Color::Blue))
+    ///     .unwrap()
+    ///     .tab_width(Some(4));
+    ///
+    /// let expected_line = Line::from(vec![
+    ///     Span::from("a   "),
+    ///     Span::from("@b").style(Style::new().bg(Color::Blue)),
+    ///     Span::from("  c"),
+    /// ]);
+    ///
+    /// assert_eq!(highlighter.highlight_line(String::from("a\t@b\tc")), expected_line);
+    /// ```
+    pub fn tab_width(self, tab_width: Option<usize>) -> Self {
+        Self { tab_width, ..self }
+    }
+
+    /// Downgrades the colors of every emitted `Span` to what a terminal with the given color
+    /// `support` can display, using [`degrade_style`]. By default colors are left as they are.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::{ColorSupport, Highlighter};
+    /// use ratatui::{
+    ///     style::{Color, Style},
+    ///     text::{Line, Span},
+    /// };
+    ///
+    /// let highlighter = Highlighter::new(r"@\w+", Style::new().bg(Color::Rgb(0, 0, 250)))
+    ///     .unwrap()
+    ///     .color_support(ColorSupport::Ansi16);
+    ///
+    /// let expected_line = Line::from(vec![Span::from("@buddy").style(Style::new().bg(Color::Blue))]);
+    ///
+    /// assert_eq!(highlighter.highlight_line(String::from("@buddy")), expected_line);
+    /// ```
+    pub fn color_support(self, color_support: ColorSupport) -> Self {
+        Self {
+            color_support,
+            ..self
+        }
+    }
+
+    /// Keeps the line endings of the text given to [`highlight_text`](Self::highlight_text) as
+    /// styled `Spans`, as described by `newlines`. Line endings are dropped by default.
+    ///
+    /// When `keep_newlines` is set, every line that was terminated by a line ending gets one
+    /// trailing `Span` holding either the raw ending or the configured glyph. A `"\r\n"` ending
+    /// is treated as a single line ending, so it yields one `Span` (or one glyph) too. The
+    /// `Span` gets the style of `newlines` patched on top of the
+    /// [`base_style`](Self::base_style), and the other options only apply to the rest of the
+    /// line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::{Highlighter, NewlineOptions};
+    /// use ratatui::{
+    ///     style::{Color, Style},
+    ///     text::{Line, Span, Text},
+    /// };
+    ///
+    /// let highlighter = Highlighter::new(r"@\w+", Style::new().bg(Color::Blue))
+    ///     .unwrap()
+    ///     .newlines(NewlineOptions {
+    ///         keep_newlines: true,
+    ///         glyph: Some('¶'),
+    ///         style: Style::new().fg(Color::DarkGray),
+    ///     });
+    ///
+    /// let expected_text = Text::from(vec![
+    ///     Line::from(vec![
+    ///         Span::from("Hi "),
+    ///         Span::from("@buddy").style(Style::new().bg(Color::Blue)),
+    ///         Span::from("¶").style(Style::new().fg(Color::DarkGray)),
+    ///     ]),
+    ///     Line::from("bye"),
+    /// ]);
+    ///
+    /// assert_eq!(highlighter.highlight_text(String::from("Hi @buddy\r\nbye")), expected_text);
+    /// ```
+    pub fn newlines(self, newlines: NewlineOptions) -> Self {
+        Self { newlines, ..self }
+    }
+
+    /// Only highlights matches inside, or only outside, of quoted regions, as described by
+    /// `quotes`. Quotes are ignored by default.
+    ///
+    /// See [`QuoteFilter`] for how quoted regions are found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::{Highlighter, QuoteFilter};
+    /// use ratatui::{
+    ///     style::{Color, Style},
+    ///     text::{Line, Span},
+    /// };
+    ///
+    /// let highlighter = Highlighter::new(r"\btrue\b", Style::new().bg(Color::Blue))
+    ///     .unwrap()
+    ///     .quotes(Some(QuoteFilter::default()));
+    ///
+    /// let expected_line = Line::from(vec![
+    ///     Span::from("enabled = "),
+    ///     Span::from("true").style(Style::new().bg(Color::Blue)),
+    ///     Span::from(r#" # not "true""#),
+    /// ]);
+    ///
+    /// assert_eq!(
+    ///     highlighter.highlight_line(String::from(r#"enabled = true # not "true""#)),
+    ///     expected_line
+    /// );
+    /// ```
+    pub fn quotes(self, quotes: Option<QuoteFilter>) -> Self {
+        Self { quotes, ..self }
+    }
+
+    /// Pre-allocates room for `spans_per_line` `Spans` in every highlighted `Line`, to avoid
+    /// growing the `Vec` of `Spans` while it's filled. Nothing is pre-allocated by default.
+    ///
+    /// This only affects allocations, never the output. It's a hint: lines with more `Spans`
+    /// still grow their `Vec`, and lines with fewer waste the extra room, so pick the typical
+    /// number of `Spans` of the lines being highlighted (about twice the number of matches).
+    pub fn with_capacity_hint(self, spans_per_line: usize) -> Self {
+        Self {
+            capacity_hint: spans_per_line,
+            ..self
+        }
+    }
+
+    /// Splits every `Span` wider than `max_span_width` columns into several `Spans` of the same
+    /// style, none of them wider than that, for renderers that assume a bounded `Span` width.
+    /// `Spans` aren't capped by default.
+    ///
+    /// `Spans` are only split between grapheme clusters, so a single grapheme wider than the cap,
+    /// like a CJK ideograph with a cap of one column, still gets a `Span` of its own. The split
+    /// happens last, after tabs are expanded, and applies to highlighted and plain `Spans`
+    /// alike; the text and styles of the `Line` stay the same. Nothing merges adjacent `Spans`
+    /// of the same style back together, so coalescing the returned `Spans` afterwards undoes the
+    /// cap.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::Highlighter;
+    /// use ratatui::{
+    ///     style::{Color, Style},
+    ///     text::{Line, Span},
+    /// };
+    ///
+    /// let highlighter = Highlighter::new(r"@\w+", Style::new().bg(Color::Blue))
+    ///     .unwrap()
+    ///     .max_span_width(Some(4));
+    ///
+    /// let expected_line = Line::from(vec![
+    ///     Span::from("Hi "),
+    ///     Span::from("@bud").style(Style::new().bg(Color::Blue)),
+    ///     Span::from("dy").style(Style::new().bg(Color::Blue)),
+    /// ]);
+    ///
+    /// assert_eq!(highlighter.highlight_line(String::from("Hi @buddy")), expected_line);
+    /// ```
+    pub fn max_span_width(self, max_span_width: Option<usize>) -> Self {
+        Self {
+            max_span_width,
+            ..self
+        }
+    }
+
+    pub(crate) fn line<'a>(&self, line: &str) -> Line<'a> {
+        self.checked_line(line).line
+    }
+
+    fn checked_line<'a>(&self, line: &str) -> Highlighted<'a> {
+        let Highlighted {
+            line: mut highlighted_line,
+            matched,
+        } = self.truncated_line(line);
+
+        if let Some(tab_width) = self.tab_width.filter(|_| line.contains('\t')) {
+            highlighted_line = expand_tabs(highlighted_line, tab_width);
+        }
+        if self.color_support != ColorSupport::TrueColor {
+            for span in &mut highlighted_line.spans {
+                span.style = degrade_style(span.style, self.color_support);
+            }
+        }
+        if let Some(max_span_width) = self.max_span_width {
+            highlighted_line = split_wide_spans(highlighted_line, max_span_width);
+        }
+
+        Highlighted {
+            line: highlighted_line,
+            matched,
+        }
+    }
+
+    fn truncated_line<'a>(&self, line: &str) -> Highlighted<'a> {
+        match self.max_line_bytes {
+            Some(max) if line.len() > max => {
+                let mut end = max;
+                while !line.is_char_boundary(end) {
+                    end -= 1;
+                }
+
+                let mut highlighted = self.highlighted_spans(&line[..end]);
+                highlighted.line.push_span(
+                    Span::from(TRUNCATION_MARKER)
+                        .style(self.base_style.patch(self.truncation_style)),
+                );
+                highlighted
+            }
+            _ => self.highlighted_spans(line),
+        }
+    }
+
+    fn highlighted_spans<'a>(&self, line: &str) -> Highlighted<'a> {
+        let highlight_style = self.base_style.patch(self.style);
+
+        match &self.quotes {
+            Some(quotes) => {
+                let regions = quotes.regions(line);
+                let matches = self
+                    .regex
+                    .find_iter(line)
+                    .filter(|m| quotes.keeps(&regions, &m.range()));
+                self.collect_spans(MatchSpans::new(
+                    line,
+                    matches,
+                    highlight_style,
+                    self.base_style,
+                ))
+            }
+            None => self.collect_spans(MatchSpans::new(
+                line,
+                self.regex.find_iter(line),
+                highlight_style,
+                self.base_style,
+            )),
+        }
+    }
+
+    fn collect_spans<'a, 'h>(
+        &self,
+        mut spans: MatchSpans<'h, impl Iterator<Item = Match<'h>>>,
+    ) -> Highlighted<'a> {
+        let mut highlighted_line = Line::from(Vec::with_capacity(self.capacity_hint));
+        highlighted_line
+            .spans
+            .extend(spans.by_ref().map(into_owned));
+
+        Highlighted {
+            line: highlighted_line,
+            matched: spans.matched(),
+        }
+    }
+
+    /// Returns the pattern the `Highlighter` was compiled from.
+    pub fn pattern(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    pub(crate) fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    /// Returns the style applied to matches.
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Returns the flags the pattern was compiled with.
+    pub fn flags(&self) -> RegexFlags {
+        self.flags
+    }
+
+    /// Returns the names of the capture groups of the pattern, in the order they appear.
+    ///
+    /// The first entry always belongs to the implicit group of the whole match and is `None`,
+    /// as is every unnamed group.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::Highlighter;
+    /// use ratatui::style::Style;
+    ///
+    /// let highlighter = Highlighter::new(r"(?<key>\w+)=(\w+)", Style::new()).unwrap();
+    ///
+    /// assert_eq!(
+    ///     highlighter.capture_names(),
+    ///     vec![None, Some(String::from("key")), None]
+    /// );
+    /// ```
+    pub fn capture_names(&self) -> Vec<Option<String>> {
+        self.regex
+            .capture_names()
+            .map(|name| name.map(String::from))
+            .collect()
+    }
+}
+
+fn expand_tabs(mut line: Line<'_>, tab_width: usize) -> Line<'_> {
+    let mut column = 0;
+
+    for span in &mut line.spans {
+        if !span.content.contains('\t') {
+            column += span.content.width();
+            continue;
+        }
+
+        let mut expanded = String::with_capacity(span.content.len());
+        for c in span.content.chars() {
+            if c == '\t' {
+                let spaces = tab_width - column % tab_width.max(1);
+                expanded.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+            } else {
+                expanded.push(c);
+                column += c.width().unwrap_or(0);
+            }
+        }
+        span.content = Cow::Owned(expanded);
+    }
+
+    line
+}
+
+fn split_wide_spans(mut line: Line<'_>, max_span_width: usize) -> Line<'_> {
+    if line
+        .spans
+        .iter()
+        .all(|span| span.content.width() <= max_span_width)
+    {
+        return line;
+    }
+
+    let mut spans = Vec::with_capacity(line.spans.len());
+    for mut span in line.spans {
+        if span.content.width() <= max_span_width {
+            spans.push(span);
+            continue;
+        }
+
+        // like in `highlight_span`, every piece is a clone of `span` with its own content
+        let content = std::mem::take(&mut span.content);
+        let mut piece = |range: Range<usize>| {
+            let mut piece = span.clone();
+            piece.content = Cow::Owned(content[range].to_string());
+            spans.push(piece);
+        };
+
+        let mut start = 0;
+        let mut width = 0;
+        for (i, grapheme) in content.grapheme_indices(true) {
+            let grapheme_width = grapheme.width();
+            if i > start && width + grapheme_width > max_span_width {
+                piece(start..i);
+                start = i;
+                width = 0;
+            }
+            width += grapheme_width;
+        }
+        piece(start..content.len());
+    }
+
+    line.spans = spans;
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{highlight_line, highlight_text};
+    use ratatui::{style::Color, text::Span};
+
+    const STYLE: Style = Style::new().bg(Color::Blue);
+    const TEXT: &str = "Hello @Henry.\nWhere @Bill is ?";
+
+    #[test]
+    fn highlighter_test() {
+        let highlighter = Highlighter::new(r"@\w+", STYLE).unwrap();
+
+        assert_eq!(
+            highlighter.highlight_line(TEXT[..13].to_string()),
+            highlight_line(TEXT[..13].to_string(), r"@\w+", STYLE)
+        );
+        assert_eq!(
+            highlighter.highlight_text(TEXT.to_string()),
+            highlight_text(TEXT.to_string(), r"@\w+", STYLE)
+        );
+        assert!(Highlighter::new(r"@(\w+", STYLE).is_err());
+    }
+
+    #[test]
+    fn unicode_test() {
+        let unicode = Highlighter::new(r"\b\w+\b", STYLE).unwrap();
+        let ascii = Highlighter::new(r"\b\w+\b", STYLE)
+            .unwrap()
+            .unicode(false)
+            .unwrap();
+
+        assert_eq!(
+            unicode.highlight_line(String::from("café au lait")),
+            Line::from(vec![
+                Span::from("café").style(STYLE),
+                Span::from(" "),
+                Span::from("au").style(STYLE),
+                Span::from(" "),
+                Span::from("lait").style(STYLE),
+            ])
+        );
+        assert_eq!(
+            ascii.highlight_line(String::from("café au lait")),
+            Line::from(vec![
+                Span::from("caf").style(STYLE),
+                Span::from("é "),
+                Span::from("au").style(STYLE),
+                Span::from(" "),
+                Span::from("lait").style(STYLE),
+            ])
+        );
+        assert!(ascii.unicode(true).is_ok());
+        assert!(Highlighter::new(r".", STYLE)
+            .unwrap()
+            .unicode(false)
+            .is_err());
+    }
+
+    #[test]
+    fn regex_flags_test() {
+        let matches = |pattern: &str, flags: RegexFlags, haystack: &str| {
+            Highlighter::with_flags(pattern, STYLE, flags)
+                .unwrap()
+                .regex
+                .find_iter(haystack)
+                .map(|m| m.as_str().to_string())
+                .collect::<Vec<_>>()
+        };
+        let default = RegexFlags::default();
+
+        assert_eq!(matches("ab", default, "AB ab"), vec!["ab"]);
+        assert_eq!(
+            matches(
+                "ab",
+                RegexFlags {
+                    case_insensitive: true,
+                    ..default
+                },
+                "AB ab"
+            ),
+            vec!["AB", "ab"]
+        );
+
+        assert_eq!(matches("^\\w", default, "a\nb"), vec!["a"]);
+        assert_eq!(
+            matches(
+                "^\\w",
+                RegexFlags {
+                    multi_line: true,
+                    ..default
+                },
+                "a\nb"
+            ),
+            vec!["a", "b"]
+        );
+
+        assert_eq!(matches("a.b", default, "a\nb"), Vec::<String>::new());
+        assert_eq!(
+            matches(
+                "a.b",
+                RegexFlags {
+                    dot_matches_new_line: true,
+                    ..default
+                },
+                "a\nb"
+            ),
+            vec!["a\nb"]
+        );
+
+        assert_eq!(matches("a+", default, "aaa"), vec!["aaa"]);
+        assert_eq!(
+            matches(
+                "a+",
+                RegexFlags {
+                    swap_greed: true,
+                    ..default
+                },
+                "aaa"
+            ),
+            vec!["a", "a", "a"]
+        );
+
+        assert_eq!(matches("a b # c", default, "a b # c ab"), vec!["a b # c"]);
+        assert_eq!(
+            matches(
+                "a b # c",
+                RegexFlags {
+                    ignore_whitespace: true,
+                    ..default
+                },
+                "a b # c ab"
+            ),
+            vec!["ab"]
+        );
+
+        assert_eq!(matches("\\w+", default, "café"), vec!["café"]);
+        assert_eq!(
+            matches(
+                "\\w+",
+                RegexFlags {
+                    unicode: false,
+                    ..default
+                },
+                "café"
+            ),
+            vec!["caf"]
+        );
+    }
+
+    #[test]
+    fn max_line_bytes_test() {
+        let marker_style = Style::new().fg(Color::DarkGray);
+        let highlighter = Highlighter::new(r"@\w+", STYLE)
+            .unwrap()
+            .max_line_bytes(Some(8))
+            .truncation_style(marker_style);
+
+        assert_eq!(
+            highlighter.highlight_text(String::from("Hello @Henry\nżółw żółw\n@Bill")),
+            Text::from(vec![
+                Line::from(vec![
+                    Span::from("Hello "),
+                    Span::from("@H").style(STYLE),
+                    Span::from(TRUNCATION_MARKER).style(marker_style),
+                ]),
+                Line::from(vec![
+                    Span::from("żółw "),
+                    Span::from(TRUNCATION_MARKER).style(marker_style),
+                ]),
+                Line::from(vec![Span::from("@Bill").style(STYLE)]),
+            ])
+        );
+        assert_eq!(
+            highlighter
+                .max_line_bytes(None)
+                .highlight_line(TEXT[..13].to_string()),
+            highlight_line(TEXT[..13].to_string(), r"@\w+", STYLE)
+        );
+    }
+
+    #[test]
+    fn highlight_span_test() {
+        let highlighter = Highlighter::new(r"@\w+", STYLE).unwrap();
+        let base = Style::new().fg(Color::Red).bg(Color::Black);
+        let patched = Style::new().fg(Color::Red).bg(Color::Blue);
+
+        let borrowed = highlighter.highlight_span(Span::from(&TEXT[..13]).style(base));
+        assert_eq!(
+            borrowed,
+            vec![
+                Span::from("Hello ").style(base),
+                Span::from("@Henry").style(patched),
+                Span::from(".").style(base),
+            ]
+        );
+        assert!(borrowed
+            .iter()
+            .all(|span| matches!(span.content, Cow::Borrowed(_))));
+
+        let owned = highlighter.highlight_span(Span::from(String::from("@Bill")));
+        assert_eq!(owned, vec![Span::from("@Bill").style(STYLE)]);
+
+        assert_eq!(highlighter.highlight_span(Span::from("")), vec![]);
+    }
+
+    #[test]
+    fn tab_width_test() {
+        let highlighter = Highlighter::new(r"@\w+", STYLE).unwrap().tab_width(Some(4));
+
+        assert_eq!(
+            highlighter.highlight_line(String::from("\t@ab\t@c\t\tx")),
+            Line::from(vec![
+                Span::from("    "),
+                Span::from("@ab").style(STYLE),
+                Span::from(" "),
+                Span::from("@c").style(STYLE),
+                Span::from("      x"),
+            ])
+        );
+        assert_eq!(
+            highlighter.highlight_line(String::from("界\t@a")),
+            Line::from(vec![Span::from("界  "), Span::from("@a").style(STYLE)])
+        );
+
+        let whitespace = Highlighter::new(r"\s+", STYLE).unwrap().tab_width(Some(4));
+        assert_eq!(
+            whitespace.highlight_line(String::from("ab\tc")),
+            Line::from(vec![
+                Span::from("ab"),
+                Span::from("  ").style(STYLE),
+                Span::from("c"),
+            ])
+        );
+
+        let word = Highlighter::new(r"\w+", STYLE).unwrap().tab_width(Some(4));
+        assert_eq!(
+            word.highlight_line(String::from("ab\tcd")),
+            Line::from(vec![
+                Span::from("ab").style(STYLE),
+                Span::from("  "),
+                Span::from("cd").style(STYLE),
+            ])
+        );
+    }
+
+    #[test]
+    fn color_support_test() {
+        let highlighter = Highlighter::new(r"@\w+", Style::new().fg(Color::Rgb(250, 0, 0)))
+            .unwrap()
+            .color_support(ColorSupport::Ansi256);
+
+        assert_eq!(
+            highlighter.highlight_line(TEXT[..13].to_string()),
+            Line::from(vec![
+                Span::from("Hello "),
+                Span::from("@Henry").style(Style::new().fg(Color::Indexed(196))),
+                Span::from("."),
+            ])
+        );
+        assert_eq!(
+            highlighter
+                .highlight_span(Span::from("@a").style(Style::new().bg(Color::Rgb(0, 0, 0)))),
+            vec![
+                Span::from("@a").style(Style::new().fg(Color::Indexed(196)).bg(Color::Indexed(16)))
+            ]
+        );
+    }
+
+    #[test]
+    fn capture_names_test() {
+        let highlighter =
+            Highlighter::new(r"(?<date>\d+-\d+)\s(\w+)\s(?P<level>\w+)", STYLE).unwrap();
+
+        assert_eq!(
+            highlighter.capture_names(),
+            vec![
+                None,
+                Some(String::from("date")),
+                None,
+                Some(String::from("level")),
+            ]
+        );
+    }
+
+    #[test]
+    fn clone_test() {
+        let flags = RegexFlags {
+            case_insensitive: true,
+            ..RegexFlags::default()
+        };
+        let highlighter = Highlighter::with_flags(r"@\w+", STYLE, flags)
+            .unwrap()
+            .tab_width(Some(2));
+        let clone = highlighter.clone();
+
+        assert_eq!(clone.pattern(), r"@\w+");
+        assert_eq!(clone.style(), STYLE);
+        assert_eq!(clone.flags(), flags);
+        assert_eq!(
+            clone.highlight_text(TEXT.to_string()),
+            highlighter.highlight_text(TEXT.to_string())
+        );
+    }
+
+    #[test]
+    fn capacity_hint_test() {
+        let highlighter = Highlighter::new(r"@\w+", STYLE).unwrap();
+
+        for quotes in [None, Some(QuoteFilter::default())] {
+            let highlighter = highlighter.clone().quotes(quotes);
+
+            for spans_per_line in [1, 4, 64] {
+                let hinted = highlighter.clone().with_capacity_hint(spans_per_line);
+                let line = hinted.highlight_line(TEXT[..13].to_string());
+
+                assert_eq!(line, highlighter.highlight_line(TEXT[..13].to_string()));
+                assert!(line.spans.capacity() >= spans_per_line);
+            }
+        }
+    }
+
+    #[test]
+    fn quotes_test() {
+        use crate::QuoteMode;
+
+        let line = String::from(r#"@a "@b \"@c\"" '@d @e' x@f"y @g"#);
+        let highlighter = Highlighter::new(r"@\w+", STYLE)
+            .unwrap()
+            .quotes(Some(QuoteFilter::default()));
+
+        assert_eq!(
+            highlighter.highlight_line(line.clone()),
+            Line::from(vec![
+                Span::from("@a").style(STYLE),
+                Span::from(r#" "@b \"@c\"" '@d @e' x"#),
+                Span::from("@f").style(STYLE),
+                Span::from(r#""y @g"#),
+            ])
+        );
+
+        let highlighter = highlighter.quotes(Some(QuoteFilter {
+            mode: QuoteMode::Inside,
+            ..QuoteFilter::default()
+        }));
+        assert_eq!(
+            highlighter.highlight_line(line.clone()),
+            Line::from(vec![
+                Span::from(r#"@a ""#),
+                Span::from("@b").style(STYLE),
+                Span::from(r#" \""#),
+                Span::from("@c").style(STYLE),
+                Span::from(r#"\"" '"#),
+                Span::from("@d").style(STYLE),
+                Span::from(" "),
+                Span::from("@e").style(STYLE),
+                Span::from(r#"' x@f"y "#),
+                Span::from("@g").style(STYLE),
+            ])
+        );
+
+        let highlighter = Highlighter::new(r#""\w+"#, STYLE)
+            .unwrap()
+            .quotes(Some(QuoteFilter::default()));
+        assert_eq!(
+            highlighter.highlight_line(String::from(r#"a"b""#)),
+            Line::from(vec![Span::from(r#"a"b""#)])
+        );
+    }
+
+    #[test]
+    fn highlight_line_checked_test() {
+        let highlighter = Highlighter::new(r"@\w+|\b", STYLE).unwrap();
+
+        for (line, matched) in [("", false), ("a b", false), ("@a", true), ("a @b c", true)] {
+            let highlighted = highlighter.highlight_line_checked(line.to_string());
+            assert_eq!(highlighted.matched, matched);
+            assert_eq!(
+                highlighted.line,
+                highlighter.highlight_line(line.to_string())
+            );
+        }
+
+        let highlighter = highlighter.max_line_bytes(Some(3));
+        assert!(
+            highlighter
+                .highlight_line_checked(String::from("@a b"))
+                .matched
+        );
+        assert!(
+            !highlighter
+                .highlight_line_checked(String::from("a b @c"))
+                .matched
+        );
+
+        let highlighter = highlighter
+            .max_line_bytes(None)
+            .quotes(Some(QuoteFilter::default()));
+        assert!(
+            !highlighter
+                .highlight_line_checked(String::from("\"@a\""))
+                .matched
+        );
+        assert!(
+            highlighter
+                .highlight_line_checked(String::from("\"@a\" @b"))
+                .matched
+        );
+    }
+
+    #[test]
+    fn max_span_width_test() {
+        let highlighter = Highlighter::new(r"@\w+", STYLE).unwrap();
+        let line = "abcde @e\u{301}\u{301}fg日本 x";
+        let capped = |max_span_width| {
+            highlighter
+                .clone()
+                .max_span_width(max_span_width)
+                .highlight_line(line.to_string())
+        };
+
+        let uncapped = capped(None);
+        assert_eq!(uncapped, highlighter.highlight_line(line.to_string()));
+        assert_eq!(uncapped, capped(Some(100)));
+        assert_eq!(
+            capped(Some(3)),
+            Line::from(vec![
+                Span::from("abc"),
+                Span::from("de "),
+                Span::from("@e\u{301}\u{301}f").style(STYLE),
+                Span::from("g日").style(STYLE),
+                Span::from("本").style(STYLE),
+                Span::from(" x"),
+            ])
+        );
+
+        for max_span_width in 0..4 {
+            let capped_line = capped(Some(max_span_width));
+            assert!(capped_line
+                .spans
+                .iter()
+                .all(|span| span.width() <= max_span_width.max(2)));
+            assert_eq!(crate::line_to_string(&capped_line), line);
+        }
+        assert_eq!(capped(Some(0)).spans.len(), 14);
+    }
+
+    #[test]
+    fn base_style_test() {
+        let base = Style::new().fg(Color::Gray).bg(Color::Black);
+        let patched = base.patch(STYLE);
+        let highlighter = Highlighter::new(r"@\w+", STYLE).unwrap();
+        let line = String::from("\"@a\" b @c d");
+
+        assert_eq!(
+            highlighter
+                .clone()
+                .base_style(Style::default())
+                .highlight_line(line.clone()),
+            highlighter.highlight_line(line.clone())
+        );
+
+        let highlighter = highlighter.base_style(base);
+        assert_eq!(
+            highlighter.highlight_line(line.clone()),
+            Line::from(vec![
+                Span::from("\"").style(base),
+                Span::from("@a").style(patched),
+                Span::from("\" b ").style(base),
+                Span::from("@c").style(patched),
+                Span::from(" d").style(base),
+            ])
+        );
+        assert_eq!(
+            highlighter
+                .clone()
+                .quotes(Some(QuoteFilter::default()))
+                .highlight_line(line.clone()),
+            Line::from(vec![
+                Span::from("\"@a\" b ").style(base),
+                Span::from("@c").style(patched),
+                Span::from(" d").style(base),
+            ])
+        );
+        assert_eq!(
+            highlighter
+                .truncation_style(Style::new().fg(Color::Red))
+                .max_line_bytes(Some(3))
+                .highlight_line(line),
+            Line::from(vec![
+                Span::from("\"").style(base),
+                Span::from("@a").style(patched),
+                Span::from(TRUNCATION_MARKER).style(base.fg(Color::Red)),
+            ])
+        );
+    }
+
+    #[test]
+    fn newlines_test() {
+        let newline_style = Style::new().fg(Color::DarkGray);
+        let text = String::from("a @b\r\n\n@c");
+        let highlighter = Highlighter::new(r"@\w+", STYLE).unwrap();
+
+        let dropped = highlighter
+            .clone()
+            .newlines(NewlineOptions::default())
+            .highlight_text(text.clone());
+        assert_eq!(dropped, crate::highlight_text(text.clone(), r"@\w+", STYLE));
+
+        let kept = highlighter
+            .clone()
+            .newlines(NewlineOptions {
+                keep_newlines: true,
+                glyph: None,
+                style: newline_style,
+            })
+            .highlight_text(text.clone());
+        assert_eq!(
+            kept,
+            Text::from(vec![
+                Line::from(vec![
+                    Span::from("a "),
+                    Span::from("@b").style(STYLE),
+                    Span::from("\r\n").style(newline_style),
+                ]),
+                Line::from(vec![Span::from("\n").style(newline_style)]),
+                Line::from(vec![Span::from("@c").style(STYLE)]),
+            ])
+        );
+
+        let base = Style::new().bg(Color::Black);
+        let glyphs = highlighter
+            .newlines(NewlineOptions {
+                keep_newlines: true,
+                glyph: Some('¶'),
+                style: newline_style,
+            })
+            .base_style(base)
+            .highlight_text(text);
+        assert_eq!(
+            glyphs.lines[0].spans[2],
+            Span::from("¶").style(base.patch(newline_style))
+        );
+        assert_eq!(
+            glyphs.lines[1].spans,
+            vec![Span::from("¶").style(base.patch(newline_style))]
+        );
+    }
+
+    #[test]
+    fn highlight_styled_line_test() {
+        let highlighter = Highlighter::new(r"@\w+", STYLE).unwrap();
+        let red = Style::new().fg(Color::Red);
+        let line = Line::from(vec![
+            Span::from("@a "),
+            Span::from(String::from("b @c")).style(red),
+            Span::from(""),
+        ])
+        .style(red)
+        .right_aligned();
+
+        let highlighted_line = highlighter.highlight_styled_line(line.clone());
+        assert_eq!(highlighted_line.style, line.style);
+        assert_eq!(highlighted_line.alignment, line.alignment);
+        assert_eq!(
+            highlighted_line,
+            Line::from(vec![
+                Span::from("@a").style(STYLE),
+                Span::from(" "),
+                Span::from("b ").style(red),
+                Span::from("@c").style(red.patch(STYLE)),
+            ])
+            .style(red)
+            .right_aligned()
+        );
+    }
+
+    #[test]
+    fn split_spans_keep_fields_test() {
+        let highlighter = Highlighter::new(r"@\w+", STYLE).unwrap();
+        let red = Style::new().fg(Color::Red);
+        let span = Span::from("ab @cd").style(red);
+        // the expected pieces are built from `span` itself, so they carry all of its fields
+        let piece = |content: &'static str, style: Style| {
+            Span {
+                content: Cow::Borrowed(content),
+                ..span.clone()
+            }
+            .style(style)
+        };
+
+        assert_eq!(
+            highlighter.highlight_span(span.clone()),
+            vec![piece("ab ", red), piece("@cd", red.patch(STYLE))]
+        );
+        assert_eq!(
+            split_wide_spans(Line::from(vec![span.clone()]), 3).spans,
+            vec![piece("ab ", red), piece("@cd", red)]
+        );
+
+        let owned = into_owned(span.clone());
+        assert!(matches!(owned.content, Cow::Owned(_)));
+        assert_eq!(owned, span);
+    }
+}
@@ -0,0 +1,163 @@
+use ratatui::{
+    style::Style,
+    text::{Line, Span, Text},
+};
+
+use crate::Highlighter;
+
+/// Stacks several [`Highlighters`](Highlighter) as layers ordered by a z-index, where every
+/// layer patches its style over the layers below it, but only where it matches.
+///
+/// Styles are patched byte by byte: every byte of a line starts out unstyled, and then, from the
+/// lowest z-index to the highest, every layer patches its style onto the bytes covered by its
+/// matches. Layers with the same z-index are applied in the order they were given. Overlapping
+/// matches of different layers therefore combine, e.g. a foreground from one layer and a
+/// background from another, and a higher layer only wins where both set the same attribute.
+/// The `Line` is then cut into `Spans` wherever the resulting style changes, so adjacent matches
+/// ending up with the same style share a `Span`.
+///
+/// Only the pattern and the style of each `Highlighter` are used; options like
+/// [`tab_width`](Highlighter::tab_width) or [`quotes`](Highlighter::quotes) are ignored. Each
+/// line is searched once per layer, and the merge costs one `Style` per byte of the line.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{Highlighter, LayeredHighlighter};
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let layered = LayeredHighlighter::new(vec![
+///     (Highlighter::new(r"@\w+", Style::new().fg(Color::Blue)).unwrap(), 0),
+///     (Highlighter::new(r"bud", Style::new().bg(Color::Yellow)).unwrap(), 1),
+/// ]);
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("Hi "),
+///     Span::from("@").style(Style::new().fg(Color::Blue)),
+///     Span::from("bud").style(Style::new().fg(Color::Blue).bg(Color::Yellow)),
+///     Span::from("dy").style(Style::new().fg(Color::Blue)),
+/// ]);
+///
+/// assert_eq!(layered.highlight_line(String::from("Hi @buddy")), expected_line);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LayeredHighlighter {
+    layers: Vec<(Highlighter, i32)>,
+}
+
+impl LayeredHighlighter {
+    /// Creates a new `LayeredHighlighter` out of `layers` and their z-index.
+    pub fn new(mut layers: Vec<(Highlighter, i32)>) -> Self {
+        // the sort is stable, keeping layers with the same z-index in order
+        layers.sort_by_key(|(_, z)| *z);
+
+        Self { layers }
+    }
+
+    /// Returns the layers and their z-index, from the lowest z-index to the highest.
+    pub fn layers(&self) -> &[(Highlighter, i32)] {
+        &self.layers
+    }
+
+    /// Creates a `Line` from `line` with the styles of all layers patched over each other.
+    pub fn highlight_line<'a>(&self, line: String) -> Line<'a> {
+        self.line(&line)
+    }
+
+    /// Creates `Text` from `text`, splitting it into lines like
+    /// [`highlight_text`](crate::highlight_text) does, with the styles of all layers patched
+    /// over each other.
+    pub fn highlight_text<'a>(&self, text: String) -> Text<'a> {
+        text.split_terminator('\n')
+            .map(|line| self.line(line))
+            .collect()
+    }
+
+    fn line<'a>(&self, line: &str) -> Line<'a> {
+        let mut styles = vec![Style::default(); line.len()];
+
+        for (highlighter, _) in &self.layers {
+            for m in highlighter
+                .regex()
+                .find_iter(line)
+                .filter(|m| !m.is_empty())
+            {
+                for style in &mut styles[m.range()] {
+                    *style = style.patch(highlighter.style());
+                }
+            }
+        }
+
+        let mut highlighted_line = Line::default();
+        let mut start = 0;
+
+        // styles only change at the bounds of matches, which are char boundaries
+        for end in 1..=line.len() {
+            if end == line.len() || styles[end] != styles[start] {
+                highlighted_line
+                    .push_span(Span::from(line[start..end].to_string()).style(styles[start]));
+                start = end;
+            }
+        }
+
+        highlighted_line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Color, Modifier};
+
+    #[test]
+    fn layered_highlighter_test() {
+        let bottom = Style::new().fg(Color::Blue);
+        let middle = Style::new().bg(Color::Yellow);
+        let top = Style::new().fg(Color::Red).add_modifier(Modifier::BOLD);
+
+        let layered = LayeredHighlighter::new(vec![
+            (Highlighter::new(r"ł\w", top).unwrap(), 2),
+            (Highlighter::new(r"\w+", bottom).unwrap(), -1),
+            (Highlighter::new(r"ół|\b", middle).unwrap(), 0),
+        ]);
+        assert_eq!(
+            layered.layers().iter().map(|(_, z)| *z).collect::<Vec<_>>(),
+            vec![-1, 0, 2]
+        );
+
+        assert_eq!(
+            layered.highlight_text(String::from("żółw, ów\n\n!")),
+            Text::from(vec![
+                Line::from(vec![
+                    Span::from("ż").style(bottom),
+                    Span::from("ó").style(bottom.patch(middle)),
+                    Span::from("ł").style(bottom.patch(middle).patch(top)),
+                    Span::from("w").style(bottom.patch(top)),
+                    Span::from(", "),
+                    Span::from("ów").style(bottom),
+                ]),
+                Line::default(),
+                Line::from(vec![Span::from("!")]),
+            ])
+        );
+    }
+
+    #[test]
+    fn same_z_index_test() {
+        let first = Style::new().fg(Color::Blue);
+        let second = Style::new().fg(Color::Red);
+
+        let layered = LayeredHighlighter::new(vec![
+            (Highlighter::new(r"a", first).unwrap(), 0),
+            (Highlighter::new(r"a", second).unwrap(), 0),
+        ]);
+
+        assert_eq!(
+            layered.highlight_line(String::from("aa b")),
+            Line::from(vec![Span::from("aa").style(second), Span::from(" b")])
+        );
+    }
+}
@@ -1,135 +1,1794 @@
-use ratatui::{
-    style::Style,
-    text::{Line, Span, Text},
+#[cfg(feature = "regex")]
+use std::{collections::HashSet, iter, ops::Range};
+
+use ratatui::style::Style;
+#[cfg(feature = "regex")]
+use ratatui::text::{Line, Span, Text};
+#[cfg(feature = "regex")]
+use regex::{Regex, RegexBuilder};
+#[cfg(feature = "regex")]
+use unicode_segmentation::UnicodeSegmentation;
+#[cfg(feature = "regex")]
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+#[cfg(feature = "regex")]
+mod cached;
+mod color;
+mod completion;
+mod glyphs;
+#[cfg(feature = "regex")]
+mod highlighter;
+#[cfg(feature = "regex")]
+mod layered;
+#[cfg(feature = "literal")]
+mod literal;
+#[cfg(feature = "regex")]
+mod matches;
+#[cfg(feature = "regex")]
+mod presets;
+#[cfg(feature = "regex")]
+mod quotes;
+#[cfg(feature = "regex")]
+mod reader;
+#[cfg(feature = "regex")]
+mod rules;
+mod segments;
+#[cfg(feature = "regex")]
+mod spans;
+mod text;
+#[cfg(feature = "regex")]
+mod theme;
+
+#[cfg(feature = "regex")]
+pub use cached::CachedHighlighter;
+pub use color::{degrade_color, degrade_style, ColorSupport};
+pub use completion::{highlight_fuzzy, highlight_prefix};
+#[cfg(feature = "regex")]
+pub use glyphs::highlight_bytes;
+pub use glyphs::{sanitize_bytes, Glyphs};
+#[cfg(feature = "regex")]
+pub use highlighter::{Highlighted, Highlighter, RegexFlags, TRUNCATION_MARKER};
+#[cfg(feature = "regex")]
+pub use layered::LayeredHighlighter;
+#[cfg(feature = "literal")]
+pub use literal::LiteralHighlighter;
+#[cfg(feature = "regex")]
+pub use matches::{
+    count_matching_lines, highlight_line_mapped, highlight_text_with_meta, matches_any,
+    split_at_match, MatchInfo,
+};
+#[cfg(feature = "regex")]
+pub use presets::{
+    find_paths, highlight_alert, highlight_code, highlight_color_names, highlight_ips,
+    highlight_ips_by, highlight_paths, highlight_phones, highlight_urls, CodeTheme, PathRef,
+    UrlLink, ALERT_STYLE, BLINKING_ALERT_STYLE, IP_PATTERN, PATH_PATTERN, PHONE_PATTERN,
+    URL_PATTERN,
 };
-use regex::Regex;
+#[cfg(feature = "regex")]
+pub use quotes::{QuoteFilter, QuoteMode};
+#[cfg(feature = "regex")]
+pub use reader::highlight_reader_chunked;
+#[cfg(feature = "regex")]
+pub use rules::{MultiHighlighter, Rule, RuleError};
+#[cfg(feature = "regex")]
+pub use segments::segment_line;
+pub use segments::{segments_to_line, segments_to_line_owned, Segment};
+#[cfg(feature = "regex")]
+pub use spans::{styled_spans, StyledSpans};
+#[cfg(feature = "regex")]
+pub use text::preserves_text;
+pub use text::{
+    collapse_duplicates, line_to_string, merge_text, text_to_string, text_visually_eq, with_zebra,
+};
+#[cfg(feature = "regex")]
+pub use theme::Theme;
+#[cfg(feature = "serde")]
+pub use theme::ThemeError;
+
+#[cfg(feature = "regex")]
+use segments::{fill_gaps, styled_line};
+#[cfg(feature = "regex")]
+use spans::into_owned;
+
+/// Compiles a literal pattern into a `&'static Regex`, validating it at compile time.
+///
+/// The pattern is checked while your crate is being built, so an invalid pattern fails the
+/// build instead of panicking at runtime. The `Regex` itself is compiled once, the first time
+/// the expression is evaluated. Requires the `macros` feature.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{highlight_text_borrowed, static_highlighter};
+/// use ratatui::style::{Color, Style};
+///
+/// let text = highlight_text_borrowed("Hi @buddy", static_highlighter!(r"@\w+"), Style::new().bg(Color::Blue));
+/// assert_eq!(text.lines[0].spans.len(), 2);
+/// ```
+///
+/// An invalid pattern is a compile error:
+///
+/// ```compile_fail
+/// let reg = tui_pattern_highlighter::static_highlighter!(r"@(\w+");
+/// ```
+#[cfg(feature = "macros")]
+pub use tui_pattern_highlighter_macros::static_highlighter;
+
+#[cfg(feature = "macros")]
+extern crate self as tui_pattern_highlighter;
+
+#[cfg(feature = "regex")]
+#[doc(hidden)]
+pub mod __private {
+    use ratatui::style::Style;
+
+    pub use regex;
+
+    use crate::Rule;
+
+    /// Creates a `Rule` of [`highlight!`](crate::highlight) labeled by its pattern.
+    pub fn rule(pattern: impl Into<String>, style: Style) -> Rule {
+        let pattern = pattern.into();
+        Rule::new(pattern.clone(), pattern, style)
+    }
+}
+
+/// Highlights `text` with several patterns at once, each with its own style, and returns the
+/// highlighted `Text`.
+///
+/// This is a shorthand for building a [`MultiHighlighter`] with a [`Rule`] per pattern and
+/// calling its [`highlight_text`](MultiHighlighter::highlight_text), so the same rules apply:
+/// the leftmost match wins, and the first pattern wins a tie. `text` can be anything a `String`
+/// can be created from, and a style can be any expression.
+///
+/// Every pattern must be a single token tree: a string literal, a variable, or any other
+/// expression wrapped in parentheses. With the `macros` feature enabled, string literal patterns
+/// are validated at compile time, like with [`static_highlighter!`](crate::static_highlighter).
+/// Any other pattern, and every pattern without the feature, is only compiled at runtime.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let blue = Style::new().fg(Color::Blue);
+/// let tag = String::from(r"#\w+");
+///
+/// let text = highlight!("@buddy likes #rust", r"@\w+" => blue, tag => blue.fg(Color::Green));
+///
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("@buddy").style(Style::new().fg(Color::Blue)),
+///     Span::from(" likes "),
+///     Span::from("#rust").style(Style::new().fg(Color::Green)),
+/// ])]);
+///
+/// assert_eq!(text, expected_text);
+/// ```
+///
+/// # Panics
+///
+/// The macro panics if a pattern compiled at runtime is an invalid regular expression.
+#[cfg(feature = "regex")]
+#[macro_export]
+macro_rules! highlight {
+    ($text:expr, $($pattern:tt => $style:expr),+ $(,)?) => {
+        $crate::MultiHighlighter::new([
+            $($crate::__private::rule($crate::__highlight_pattern!($pattern), $style)),+
+        ])
+        .unwrap_or_else(|err| panic!("{err}"))
+        .highlight_text(::std::string::String::from($text))
+    };
+}
+
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __highlight_pattern {
+    ($pattern:literal) => {{
+        // the closure is never called, it's only there for the compile-time check
+        let _ = || $crate::static_highlighter!($pattern);
+        $pattern
+    }};
+    ($pattern:tt) => {{
+        // a pattern wrapped in parentheses is fine here, they're needed to make it a single
+        // token tree
+        #[allow(unused_parens)]
+        let pattern = $pattern;
+        pattern
+    }};
+}
+
+#[cfg(all(feature = "regex", not(feature = "macros")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __highlight_pattern {
+    ($pattern:tt) => {{
+        // a pattern wrapped in parentheses is fine here, they're needed to make it a single
+        // token tree
+        #[allow(unused_parens)]
+        let pattern = $pattern;
+        pattern
+    }};
+}
+
+/// Creates a `Line` from the given `line` argument and adds `highlight_style` to `Spans` that match the pattern.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_line;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let line = String::from("Hi @buddy");
+/// let pattern = r"@\w+";
+/// let highlight_style = Style::new().bg(Color::Blue);
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("Hi "),
+///     Span::from("@buddy").style(Style::new().bg(Color::Blue)),
+/// ]);
+///
+/// assert_eq!(highlight_line(line, pattern, highlight_style), expected_line);
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_line<'a>(
+    line: String,
+    pattern: impl AsRef<str>,
+    highlight_style: Style,
+) -> Line<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+    highlight_line_with(&line, &reg, highlight_style)
+}
+
+#[cfg(feature = "regex")]
+pub(crate) fn highlight_line_with<'a>(line: &str, reg: &Regex, highlight_style: Style) -> Line<'a> {
+    highlight_line_styled(line, reg, highlight_style, Style::default())
+}
+
+#[cfg(feature = "regex")]
+fn highlight_line_styled<'a>(
+    line: &str,
+    reg: &Regex,
+    highlight_style: Style,
+    rest_style: Style,
+) -> Line<'a> {
+    Line::from(
+        StyledSpans::new(line, reg, highlight_style, rest_style)
+            .map(into_owned)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Creates a `Line` like [`highlight_line`], but also applies `rest_style` to the text that
+/// doesn't match the pattern, so the matches stand out (e.g. by dimming everything else).
+///
+/// A line without any match is returned as a single `Span` styled with `rest_style`.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `match_style` - The style to be applied to the matching text.
+/// * `rest_style` - The style to be applied to the rest of the text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_line_focus;
+/// use ratatui::{
+///     style::{Color, Style, Stylize},
+///     text::{Line, Span},
+/// };
+///
+/// let line = String::from("Hi @buddy");
+/// let match_style = Style::new().bg(Color::Blue);
+/// let rest_style = Style::new().dim();
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("Hi ").style(Style::new().dim()),
+///     Span::from("@buddy").style(Style::new().bg(Color::Blue)),
+/// ]);
+///
+/// assert_eq!(
+///     highlight_line_focus(line, r"@\w+", match_style, rest_style),
+///     expected_line
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_line_focus<'a>(
+    line: String,
+    pattern: impl AsRef<str>,
+    match_style: Style,
+    rest_style: Style,
+) -> Line<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+    highlight_line_styled(&line, &reg, match_style, rest_style)
+}
+
+/// Creates a `Line` from the given `line` argument that styles the first match of
+/// `head_pattern` with `head_style` and everything after it with `tail_style`, for lines made
+/// of a leading token and a remainder, like `[2024-01-01] message`.
+///
+/// Text before the head is left plain, and so is the whole line, as a single `Span`, when
+/// `head_pattern` doesn't match. Later matches of `head_pattern` are part of the tail.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `head_pattern` - A regular expression pattern to match the head.
+/// * `head_style` - The style to be applied to the head.
+/// * `tail_style` - The style to be applied to the text after the head.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_head_tail;
+/// use ratatui::{
+///     style::{Color, Style, Stylize},
+///     text::{Line, Span},
+/// };
+///
+/// let line = String::from("[2024-01-01] disk full");
+/// let head_style = Style::new().fg(Color::Blue);
+/// let tail_style = Style::new().bold();
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("[2024-01-01]").style(Style::new().fg(Color::Blue)),
+///     Span::from(" disk full").style(Style::new().bold()),
+/// ]);
+///
+/// assert_eq!(
+///     highlight_head_tail(line, r"\[[^\]]*\]", head_style, tail_style),
+///     expected_line
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_head_tail<'a>(
+    line: String,
+    head_pattern: impl AsRef<str>,
+    head_style: Style,
+    tail_style: Style,
+) -> Line<'a> {
+    let reg = Regex::new(head_pattern.as_ref()).unwrap();
+
+    let mut highlighted_line = Line::default();
+    let Some(head) = reg.find_iter(&line).find(|m| !m.is_empty()) else {
+        if !line.is_empty() {
+            highlighted_line.push_span(Span::from(line));
+        }
+        return highlighted_line;
+    };
+
+    if head.start() > 0 {
+        highlighted_line.push_span(Span::from(line[..head.start()].to_string()));
+    }
+    highlighted_line.push_span(Span::from(head.as_str().to_string()).style(head_style));
+    if line.len() > head.end() {
+        highlighted_line.push_span(Span::from(line[head.end()..].to_string()).style(tail_style));
+    }
+
+    highlighted_line
+}
+
+/// Creates a `Line` from the given `line` argument that styles the first and last grapheme of
+/// every match with `edge_style` and the graphemes in between with `body_style`, for a "pill"
+/// look with distinct edges.
+///
+/// A match of a single grapheme is styled with `edge_style` only, and a match of two graphemes
+/// gets two edge `Spans` without a body. Matches are split into graphemes rather than chars, so
+/// an edge is never a dangling combining mark or half of an emoji. Like everywhere else, empty
+/// matches are ignored.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `edge_style` - The style to be applied to the first and last grapheme of a match.
+/// * `body_style` - The style to be applied to the rest of a match.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_line_pill;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let line = String::from("[tag]");
+/// let edge_style = Style::new().fg(Color::Blue);
+/// let body_style = Style::new().bg(Color::Blue);
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("[").style(Style::new().fg(Color::Blue)),
+///     Span::from("tag").style(Style::new().bg(Color::Blue)),
+///     Span::from("]").style(Style::new().fg(Color::Blue)),
+/// ]);
+///
+/// assert_eq!(
+///     highlight_line_pill(line, r"\[\w+\]", edge_style, body_style),
+///     expected_line
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_line_pill<'a>(
+    line: String,
+    pattern: impl AsRef<str>,
+    edge_style: Style,
+    body_style: Style,
+) -> Line<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+
+    let pieces = reg.find_iter(&line).flat_map(|m| {
+        let text = m.as_str();
+        let first_end = m.start() + text.graphemes(true).next().map_or(0, str::len);
+        // a single grapheme is all edge, and leaves nothing for the body and the last edge
+        let last_start = m.start()
+            + text
+                .grapheme_indices(true)
+                .next_back()
+                .map_or(0, |(i, _)| i);
+        let last_start = last_start.max(first_end);
+
+        [
+            (m.start()..first_end, edge_style),
+            (first_end..last_start, body_style),
+            (last_start..m.end(), edge_style),
+        ]
+    });
+
+    styled_line(&line, pieces)
+}
+
+/// Creates a `Line` exactly `width` columns wide that shows the first match of `pattern` in
+/// `line` styled with `match_style`, centered between as much of its context as fits, for
+/// fixed-width status and preview bars.
+///
+/// The columns left by the match are split evenly between the text before and after it. When
+/// the context on a side is too long, it's cut at a char boundary, and an ellipsis (`…`)
+/// replaces the part that was cut; when it's too short, it's padded with spaces so the match
+/// stays centered. A match wider than `width` is cut the same way, and a line without any match
+/// shows its start. Widths are display widths, so wide chars like CJK ideographs count as two
+/// columns, and padding makes up for a wide char that doesn't fit.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `width` - The width of the returned line, in columns.
+/// * `match_style` - The style to be applied to the matching text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::center_on_match;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let line = String::from("the quick brown fox jumps over the lazy dog");
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("…own "),
+///     Span::from("fox").style(Style::new().bg(Color::Blue)),
+///     Span::from(" jum…"),
+/// ]);
+///
+/// assert_eq!(center_on_match(line, "fox", 13, Style::new().bg(Color::Blue)), expected_line);
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn center_on_match<'a>(
+    line: String,
+    pattern: impl AsRef<str>,
+    width: u16,
+    match_style: Style,
+) -> Line<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+    let width = usize::from(width);
+
+    let mut centered_line = Line::default();
+    let mut push = |text: String, style: Style| {
+        if !text.is_empty() {
+            centered_line.push_span(Span::from(text).style(style));
+        }
+    };
+
+    match reg.find_iter(&line).find(|m| !m.is_empty()) {
+        Some(m) if m.as_str().width() < width => {
+            let context_width = width - m.as_str().width();
+            let before_width = context_width / 2;
+
+            push(fit_end(&line[..m.start()], before_width), Style::default());
+            push(m.as_str().to_string(), match_style);
+            push(
+                fit_start(&line[m.end()..], context_width - before_width),
+                Style::default(),
+            );
+        }
+        Some(m) => push(fit_start(m.as_str(), width), match_style),
+        None => push(fit_start(&line, width), Style::default()),
+    }
+
+    centered_line
+}
+
+/// Returns the start of `text` in exactly `width` columns, ending with an ellipsis if it had to
+/// be cut and padded with spaces otherwise.
+#[cfg(feature = "regex")]
+fn fit_start(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return format!("{text}{}", " ".repeat(width - text.width()));
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut taken = 0;
+    let mut end = 0;
+    for (i, c) in text.char_indices() {
+        let c_width = c.width().unwrap_or(0);
+        if taken + c_width > width - 1 {
+            break;
+        }
+        taken += c_width;
+        end = i + c.len_utf8();
+    }
+
+    format!("{}…{}", &text[..end], " ".repeat(width - 1 - taken))
+}
+
+/// Returns the end of `text` in exactly `width` columns, starting with an ellipsis if it had to
+/// be cut and padded with spaces otherwise.
+#[cfg(feature = "regex")]
+fn fit_end(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return format!("{}{text}", " ".repeat(width - text.width()));
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut taken = 0;
+    let mut start = text.len();
+    for (i, c) in text.char_indices().rev() {
+        let c_width = c.width().unwrap_or(0);
+        if taken + c_width > width - 1 {
+            break;
+        }
+        taken += c_width;
+        start = i;
+    }
+
+    format!("{}…{}", " ".repeat(width - 1 - taken), &text[start..])
+}
+
+/// Creates a `Line` like [`highlight_line`], but only highlights matches starting inside the
+/// byte `region` of the line, leaving the text outside of it unstyled.
+///
+/// Matching starts at `region.start`, so the text before the region isn't scanned, and it stops
+/// at the first match starting after `region.end`. A match straddling the region's end is
+/// highlighted as a whole, since it starts inside the region, while a match straddling its
+/// start is not found at all. Zero-width matches are ignored. A `region` reaching past the end
+/// of the line is clamped to it.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `region` - The range of bytes in which matches have to start.
+/// * `highlight_style` - The style to be applied to the matching text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_line_range;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let line = String::from("@a @b @c");
+/// let highlight_style = Style::new().bg(Color::Blue);
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("@a "),
+///     Span::from("@b").style(Style::new().bg(Color::Blue)),
+///     Span::from(" @c"),
+/// ]);
+///
+/// assert_eq!(
+///     highlight_line_range(line, r"@\w+", 2..4, highlight_style),
+///     expected_line
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression, or if
+/// `region.start` doesn't lie on a char boundary of the line.
+#[cfg(feature = "regex")]
+pub fn highlight_line_range<'a>(
+    line: String,
+    pattern: impl AsRef<str>,
+    region: Range<usize>,
+    highlight_style: Style,
+) -> Line<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+    let end = region.end.min(line.len());
+
+    let mut at = region.start.min(end);
+    let matches = iter::from_fn(|| {
+        while at < end {
+            let m = reg.find_at(&line, at).filter(|m| m.start() < end)?;
+
+            if m.is_empty() {
+                at += line[at..].chars().next().map_or(1, char::len_utf8);
+                continue;
+            }
+
+            at = m.end();
+            return Some((m.range(), highlight_style));
+        }
+        None
+    });
+
+    styled_line(&line, matches)
+}
+
+/// Creates a `Line` like [`highlight_line`], and additionally patches `selection_style` onto
+/// the byte range `selection`, like an editor rendering a selection over search results.
+///
+/// The `Spans` are split at the edges of both the matches and the selection. Where they
+/// overlap, `selection_style` is patched on top of `match_style`, so the selection wins for
+/// the properties both styles set, e.g. the background. The selection is clamped to the
+/// length of the line, and an empty selection doesn't select anything.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `match_style` - The style to be applied to the matching text.
+/// * `selection` - The byte range of the selected text.
+/// * `selection_style` - The style to be patched onto the selected text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_line_with_selection;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let line = String::from("Hi @buddy");
+/// let match_style = Style::new().fg(Color::Blue);
+/// let selection_style = Style::new().bg(Color::DarkGray);
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("Hi"),
+///     Span::from(" ").style(Style::new().bg(Color::DarkGray)),
+///     Span::from("@b").style(Style::new().fg(Color::Blue).bg(Color::DarkGray)),
+///     Span::from("uddy").style(Style::new().fg(Color::Blue)),
+/// ]);
+///
+/// assert_eq!(
+///     highlight_line_with_selection(line, r"@\w+", match_style, 2..5, selection_style),
+///     expected_line
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression, and panics
+/// if the edges of `selection` aren't char boundaries.
+#[cfg(feature = "regex")]
+pub fn highlight_line_with_selection<'a>(
+    line: String,
+    pattern: impl AsRef<str>,
+    match_style: Style,
+    selection: Range<usize>,
+    selection_style: Style,
+) -> Line<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+    let selection = selection.start.min(line.len())..selection.end.min(line.len());
+
+    let pieces = fill_gaps(line.len(), reg.find_iter(&line)).flat_map(|(range, m)| {
+        let style = if m.is_some() {
+            match_style
+        } else {
+            Style::default()
+        };
+        let (selection_start, selection_end) = if selection.is_empty() {
+            (range.end, range.end)
+        } else {
+            let start = selection.start.clamp(range.start, range.end);
+            (start, selection.end.clamp(start, range.end))
+        };
+
+        [
+            (range.start..selection_start, style),
+            (selection_start..selection_end, style.patch(selection_style)),
+            (selection_end..range.end, style),
+        ]
+    });
+
+    styled_line(&line, pieces)
+}
+
+/// Creates a `Line` from the given `line` argument and styles only the part of every match that
+/// comes after the last `delimiter` in it, like the `function` in `module::function`.
+///
+/// The part of a match up to and including its last `delimiter` stays plain. A match without
+/// `delimiter` has no prefix, so it's styled as a whole, as it is when `delimiter` is empty,
+/// and nothing of a match ending with `delimiter` is styled.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `delimiter` - The delimiter separating the prefix of a match from the styled part.
+/// * `highlight_style` - The style to be applied to the part of a match after `delimiter`.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_line_suffix;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let line = String::from("call std::mem::take or drop");
+/// let highlight_style = Style::new().bg(Color::Blue);
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("call std::mem::"),
+///     Span::from("take").style(Style::new().bg(Color::Blue)),
+///     Span::from(" or "),
+///     Span::from("drop").style(Style::new().bg(Color::Blue)),
+/// ]);
+///
+/// assert_eq!(
+///     highlight_line_suffix(line, r"(?:\w+::)*(?:take|drop)", "::", highlight_style),
+///     expected_line
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_line_suffix<'a>(
+    line: String,
+    pattern: impl AsRef<str>,
+    delimiter: &str,
+    highlight_style: Style,
+) -> Line<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+
+    let suffixes = reg.find_iter(&line).map(|m| {
+        let start = match m.as_str().rfind(delimiter) {
+            Some(i) if !delimiter.is_empty() => m.start() + i + delimiter.len(),
+            _ => m.start(),
+        };
+        (start..m.end(), highlight_style)
+    });
+
+    styled_line(&line, suffixes)
+}
+
+/// Creates a `Line` like [`highlight_line`], and additionally patches `group_style` onto the
+/// text of a capture group inside every match.
+///
+/// Each match produces up to three `Spans`: the text of the match before the group and after
+/// it, styled with `match_style`, and the group itself, styled with `match_style` patched by
+/// `group_style`. When the group doesn't participate in a match, or doesn't exist at all, the
+/// whole match is styled with `match_style`.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `match_style` - The style to be applied to the matching text.
+/// * `group` - The index of the capture group to be emphasized.
+/// * `group_style` - The style to be patched onto the capture group's text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_line_match_and_group;
+/// use ratatui::{
+///     style::{Color, Modifier, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let line = String::from("see #42!");
+/// let match_style = Style::new().fg(Color::Blue);
+/// let group_style = Style::new().add_modifier(Modifier::BOLD);
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("see "),
+///     Span::from("#").style(Style::new().fg(Color::Blue)),
+///     Span::from("42").style(Style::new().fg(Color::Blue).add_modifier(Modifier::BOLD)),
+///     Span::from("!"),
+/// ]);
+///
+/// assert_eq!(
+///     highlight_line_match_and_group(line, r"#(\d+)", match_style, 1, group_style),
+///     expected_line
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_line_match_and_group<'a>(
+    line: String,
+    pattern: impl AsRef<str>,
+    match_style: Style,
+    group: usize,
+    group_style: Style,
+) -> Line<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+
+    let pieces = reg.captures_iter(&line).flat_map(|caps| {
+        let m = caps.get(0).unwrap();
+        // without the group, the whole match ends up in the first piece
+        let g = caps
+            .get(group)
+            .filter(|g| !g.is_empty())
+            .map_or(m.end()..m.end(), |g| g.range());
+
+        [
+            (m.start()..g.start, match_style),
+            (g.clone(), match_style.patch(group_style)),
+            (g.end..m.end(), match_style),
+        ]
+    });
+
+    styled_line(&line, pieces)
+}
+
+/// Creates a `Line` from the given `line` argument and styles the value of every key-value
+/// pair matched by `pattern` with the style `f` computes from the key and the value.
+///
+/// `pattern` must have exactly two capture groups: the first one is the key and the second one
+/// is the value. Only the value is styled, the rest of the match (the key, the separator) is
+/// left plain. When the key group doesn't participate in a match, `f` gets an empty key, and
+/// when the value group doesn't participate or is empty, nothing is styled for that match.
+/// `f` can return `Style::default()` for keys it doesn't recognize.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `pattern` - A regular expression pattern with a key group and a value group.
+/// * `f` - A closure computing the style of a value from its key and the value itself.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_line_kv;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let line = String::from("status=500 path=/");
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("status="),
+///     Span::from("500").style(Style::new().fg(Color::Red)),
+///     Span::from(" path="),
+///     Span::from("/"),
+/// ]);
+///
+/// assert_eq!(
+///     highlight_line_kv(line, r"(\w+)=(\S+)", |key, value| match key {
+///         "status" if value.starts_with('5') => Style::new().fg(Color::Red),
+///         _ => Style::default(),
+///     }),
+///     expected_line
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression, and panics
+/// if it doesn't have exactly two capture groups.
+#[cfg(feature = "regex")]
+pub fn highlight_line_kv<'a, F: FnMut(&str, &str) -> Style>(
+    line: String,
+    pattern: impl AsRef<str>,
+    mut f: F,
+) -> Line<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+    assert_eq!(
+        reg.captures_len(),
+        3,
+        "the pattern must have exactly two capture groups, the key and the value"
+    );
+
+    let values = reg.captures_iter(&line).filter_map(|caps| {
+        let value = caps.get(2).filter(|value| !value.is_empty())?;
+        let key = caps.get(1).map_or("", |key| key.as_str());
+
+        Some((value.range(), f(key, value.as_str())))
+    });
+
+    styled_line(&line, values)
+}
+
+/// Creates a `Line` like [`highlight_line`], but computes the style of every match with `f`.
+///
+/// `f` is called once per match, in order, with the zero-based index of the match and the
+/// matched text, so the indices it receives are strictly ascending with none skipped. This
+/// covers things like fading out later matches or cycling through a palette.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `f` - A closure computing the style of a match from its index and text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_line_indexed;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let line = String::from("@a @b @c");
+/// let palette = [Color::Blue, Color::Green];
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("@a").style(Style::new().bg(Color::Blue)),
+///     Span::from(" "),
+///     Span::from("@b").style(Style::new().bg(Color::Green)),
+///     Span::from(" "),
+///     Span::from("@c").style(Style::new().bg(Color::Blue)),
+/// ]);
+///
+/// assert_eq!(
+///     highlight_line_indexed(line, r"@\w+", |i, _| Style::new().bg(palette[i % palette.len()])),
+///     expected_line
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_line_indexed<'a, F: FnMut(usize, &str) -> Style>(
+    line: String,
+    pattern: impl AsRef<str>,
+    mut f: F,
+) -> Line<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+
+    let matches = reg
+        .find_iter(&line)
+        .filter(|m| !m.is_empty())
+        .enumerate()
+        .map(|(i, m)| (m.range(), f(i, m.as_str())));
+
+    styled_line(&line, matches)
+}
+
+/// Creates a `Line` from the given `line` argument, letting `f` build the `Span` of every
+/// segment.
+///
+/// The line is split into segments exactly like [`highlight_line`] does, and `f` is called for
+/// each one, in order, with its text and whether it's a match. Whatever `f` returns is pushed
+/// as is, which gives full control over the spans' content and style, e.g. to encode an ID the
+/// app can later look up for click handling.
+///
+/// `f` should return a span whose content is the segment's text: the line only reads the same
+/// as the input (see [`preserves_text`]) if it does.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `f` - A closure building the span of a segment from its text and whether it's a match.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_line_build;
+/// use ratatui::{
+///     style::{Color, Style, Stylize},
+///     text::{Line, Span},
+/// };
+///
+/// let line = String::from("Hi @buddy");
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("Hi ").dim(),
+///     Span::from("@buddy").style(Style::new().bg(Color::Blue)),
+/// ]);
+///
+/// assert_eq!(
+///     highlight_line_build(line, r"@\w+", |text, is_match| {
+///         if is_match {
+///             Span::from(text.to_string()).style(Style::new().bg(Color::Blue))
+///         } else {
+///             Span::from(text.to_string()).dim()
+///         }
+///     }),
+///     expected_line
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_line_build<'a, F: FnMut(&str, bool) -> Span<'a>>(
+    line: String,
+    pattern: impl AsRef<str>,
+    mut f: F,
+) -> Line<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+
+    fill_gaps(line.len(), reg.find_iter(&line))
+        .map(|(range, m)| f(&line[range], m.is_some()))
+        .collect()
+}
+
+/// Creates `Text` from the given `line` argument and adds `highlight_style` to `Spans` that match the pattern.
+/// When the '\n' character is encountered, a new `Line` begins.
+///
+/// # Arguments
+///
+/// * `text` - A string slice that holds the text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_text;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = String::from("Hi @buddy\n@stranger hello");
+/// let pattern = r"@\w+";
+/// let highlight_style = Style::new().bg(Color::Blue);
+///
+/// let expected_text = Text::from(vec![
+///     Line::from(vec![
+///         Span::from("Hi "),
+///         Span::from("@buddy").style(Style::new().bg(Color::Blue)),
+///     ]),
+///     Line::from(vec![
+///         Span::from("@stranger").style(Style::new().bg(Color::Blue)),
+///         Span::from(" hello"),
+///     ]),
+/// ]);
+///
+/// assert_eq!(highlight_text(text, pattern, highlight_style), expected_text);
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_text<'a>(
+    text: String,
+    pattern: impl AsRef<str>,
+    highlight_style: Style,
+) -> Text<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+    highlight_text_with(&text, &reg, highlight_style)
+}
+
+/// Creates `Text` like [`highlight_text`], but only styles the first occurrence of every
+/// distinct match with `first_style`, and its repeats with `repeat_style`, e.g. to tell new
+/// events apart from repeated ones in a log.
+///
+/// Matches are compared by their text, exactly, and the matches seen so far are remembered
+/// across lines, so a repeat is spotted wherever it is in `text`. `text` is still searched only
+/// once.
+///
+/// # Arguments
+///
+/// * `text` - A string slice that holds the text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `first_style` - The style to be applied to the first occurrence of every match.
+/// * `repeat_style` - The style to be applied to the repeats.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_text_dedup;
+/// use ratatui::{
+///     style::{Color, Modifier, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = String::from("E42 failed\nE7 failed\nE42 failed");
+/// let first_style = Style::new().fg(Color::Red);
+/// let repeat_style = Style::new().add_modifier(Modifier::DIM);
+///
+/// let expected_text = Text::from(vec![
+///     Line::from(vec![Span::from("E42").style(first_style), Span::from(" failed")]),
+///     Line::from(vec![Span::from("E7").style(first_style), Span::from(" failed")]),
+///     Line::from(vec![Span::from("E42").style(repeat_style), Span::from(" failed")]),
+/// ]);
+///
+/// assert_eq!(highlight_text_dedup(text, r"E\d+", first_style, repeat_style), expected_text);
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_text_dedup<'a>(
+    text: String,
+    pattern: impl AsRef<str>,
+    first_style: Style,
+    repeat_style: Style,
+) -> Text<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+
+    let mut seen = HashSet::new();
+
+    text.split_terminator('\n')
+        .map(|line| {
+            let matches = reg.find_iter(line).filter(|m| !m.is_empty()).map(|m| {
+                let style = if seen.insert(m.as_str()) {
+                    first_style
+                } else {
+                    repeat_style
+                };
+                (m.range(), style)
+            });
+
+            styled_line(line, matches)
+        })
+        .collect()
+}
+
+/// Creates `Text` like [`highlight_text`], and gives every `Line` a base style computed from
+/// its content.
+///
+/// `line_style` is called exactly once per line, in order, with the line's text, and its result
+/// becomes the style of the whole `Line`. The matches keep `match_style` on their `Spans`,
+/// and since a `Span`'s style is patched on top of its `Line`'s style when rendered, the
+/// matches are drawn with the base style patched by `match_style`.
+///
+/// # Arguments
+///
+/// * `text` - A string slice that holds the text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `match_style` - The style to be applied to the matching text.
+/// * `line_style` - A closure computing the base style of each line.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_text_lines_with;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = String::from("ERROR in @db\nok");
+/// let match_style = Style::new().bg(Color::Blue);
+///
+/// let expected_text = Text::from(vec![
+///     Line::from(vec![
+///         Span::from("ERROR in "),
+///         Span::from("@db").style(Style::new().bg(Color::Blue)),
+///     ])
+///     .style(Style::new().fg(Color::Red)),
+///     Line::from("ok"),
+/// ]);
+///
+/// let returned_text = highlight_text_lines_with(text, r"@\w+", match_style, |line| {
+///     if line.starts_with("ERROR") {
+///         Style::new().fg(Color::Red)
+///     } else {
+///         Style::new()
+///     }
+/// });
+///
+/// assert_eq!(returned_text, expected_text);
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_text_lines_with<'a, F: FnMut(&str) -> Style>(
+    text: String,
+    pattern: impl AsRef<str>,
+    match_style: Style,
+    mut line_style: F,
+) -> Text<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+
+    text.split_terminator('\n')
+        .map(|line| highlight_line_with(line, &reg, match_style).style(line_style(line)))
+        .collect()
+}
+
+/// Creates `Text` like [`highlight_text`], but every `Span` borrows its content from `text`.
+///
+/// No `String` is allocated for any of the `Spans`; the only allocations are the `Vec`s backing
+/// the `Lines` and the `Text`. Together with a precompiled `Regex`, this is the fastest way to
+/// highlight a buffer that outlives the frame it's rendered in.
+///
+/// # Arguments
+///
+/// * `text` - A string slice that holds the text to be highlighted.
+/// * `reg` - A compiled regular expression to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_text_borrowed;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+/// use regex::Regex;
+///
+/// let text = "Hi @buddy\n@stranger hello";
+/// let reg = Regex::new(r"@\w+").unwrap();
+/// let highlight_style = Style::new().bg(Color::Blue);
+///
+/// let expected_text = Text::from(vec![
+///     Line::from(vec![
+///         Span::from("Hi "),
+///         Span::from("@buddy").style(Style::new().bg(Color::Blue)),
+///     ]),
+///     Line::from(vec![
+///         Span::from("@stranger").style(Style::new().bg(Color::Blue)),
+///         Span::from(" hello"),
+///     ]),
+/// ]);
+///
+/// assert_eq!(highlight_text_borrowed(text, &reg, highlight_style), expected_text);
+/// ```
+#[cfg(feature = "regex")]
+pub fn highlight_text_borrowed<'a>(text: &'a str, reg: &Regex, highlight_style: Style) -> Text<'a> {
+    text.split_terminator('\n')
+        .map(|line| Line::from(styled_spans(line, reg, highlight_style).collect::<Vec<_>>()))
+        .collect()
+}
+
+/// Creates a `Line` like [`highlight_line`] out of a precompiled `Regex`, along with its
+/// display width, measured while the `Spans` are built instead of in a second pass.
+///
+/// The width is the one the `Line` takes up on screen, as measured by `unicode-width`: wide
+/// chars like CJK ideographs count as two columns, and zero-width chars like combining accents
+/// don't count at all. It saturates at `u16::MAX`, the widest a terminal area can be anyway.
+/// The `Spans` borrow their content from `line`.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `reg` - A compiled regular expression to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_line_with_width;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+/// use regex::Regex;
+///
+/// let reg = Regex::new(r"@\w+").unwrap();
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("日本 "),
+///     Span::from("@buddy").style(Style::new().bg(Color::Blue)),
+/// ]);
+///
+/// assert_eq!(
+///     highlight_line_with_width("日本 @buddy", &reg, Style::new().bg(Color::Blue)),
+///     (expected_line, 11)
+/// );
+/// ```
+#[cfg(feature = "regex")]
+pub fn highlight_line_with_width<'a>(
+    line: &'a str,
+    reg: &Regex,
+    highlight_style: Style,
+) -> (Line<'a>, u16) {
+    let mut width = 0;
+    let highlighted_line = styled_spans(line, reg, highlight_style)
+        .inspect(|span| width += span.content.width())
+        .collect::<Line>();
+
+    (highlighted_line, u16::try_from(width).unwrap_or(u16::MAX))
+}
+
+/// Creates `Text` like [`highlight_text_borrowed`], but only for the `height` lines starting at
+/// line `offset`, which is all a scrolled view needs to render.
+///
+/// Lines are split lazily, so the lines before the viewport are only scanned for their line
+/// endings, the ones after it aren't looked at at all, and only the visible lines are
+/// highlighted and allocated. An `offset` past the end of the text gives empty `Text`.
+///
+/// # Arguments
+///
+/// * `text` - A string slice that holds the text to be highlighted.
+/// * `reg` - A compiled regular expression to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+/// * `offset` - The index of the first visible line.
+/// * `height` - The number of visible lines.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_viewport;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+/// use regex::Regex;
+///
+/// let text = "a\nb @c\nd\ne";
+/// let reg = Regex::new(r"@\w+").unwrap();
+///
+/// let expected_text = Text::from(vec![
+///     Line::from(vec![Span::from("b "), Span::from("@c").style(Style::new().bg(Color::Blue))]),
+///     Line::from("d"),
+/// ]);
+///
+/// assert_eq!(highlight_viewport(text, &reg, Style::new().bg(Color::Blue), 1, 2), expected_text);
+/// ```
+#[cfg(feature = "regex")]
+pub fn highlight_viewport<'a>(
+    text: &'a str,
+    reg: &Regex,
+    highlight_style: Style,
+    offset: usize,
+    height: usize,
+) -> Text<'a> {
+    text.split_terminator('\n')
+        .skip(offset)
+        .take(height)
+        .map(|line| Line::from(styled_spans(line, reg, highlight_style).collect::<Vec<_>>()))
+        .collect()
+}
+
+/// Highlights the lines of `text` like [`highlight_text_borrowed`], but yields them last line
+/// first, for views rendered bottom-up, like a chat pinned to the newest message.
+///
+/// The text is split from its end, so lines are the same as the ones of
+/// [`highlight_text_borrowed`], just in reverse order, and nothing needs to be collected to be
+/// reversed. Lines are highlighted lazily, so stopping once the view is full skips the rest.
+///
+/// # Arguments
+///
+/// * `text` - A string slice that holds the text to be highlighted.
+/// * `reg` - A compiled regular expression to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_text_rev;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+/// use regex::Regex;
+///
+/// let text = "a\nb @c\nd\n";
+/// let reg = Regex::new(r"@\w+").unwrap();
+///
+/// let expected_lines = vec![
+///     Line::from("d"),
+///     Line::from(vec![Span::from("b "), Span::from("@c").style(Style::new().bg(Color::Blue))]),
+/// ];
+///
+/// assert_eq!(
+///     highlight_text_rev(text, &reg, Style::new().bg(Color::Blue)).take(2).collect::<Vec<_>>(),
+///     expected_lines
+/// );
+/// ```
+#[cfg(feature = "regex")]
+pub fn highlight_text_rev<'a>(
+    text: &'a str,
+    reg: &'a Regex,
+    highlight_style: Style,
+) -> impl Iterator<Item = Line<'a>> + 'a {
+    text.split_terminator('\n')
+        .rev()
+        .map(move |line| Line::from(styled_spans(line, reg, highlight_style).collect::<Vec<_>>()))
+}
+
+/// Controls what happens to line endings when splitting text into lines, set with
+/// [`Highlighter::newlines`].
+///
+/// The default drops line endings, which is what [`highlight_text`] does.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NewlineOptions {
+    /// Keep each line ending as a trailing `Span` of its line.
+    pub keep_newlines: bool,
+    /// A visible glyph (e.g. `'¶'`) to show instead of the raw line ending.
+    pub glyph: Option<char>,
+    /// The style of the trailing line ending `Span`.
+    pub style: Style,
+}
+
+#[cfg(feature = "regex")]
+pub(crate) fn highlight_text_with<'a>(text: &str, reg: &Regex, highlight_style: Style) -> Text<'a> {
+    text.split_terminator('\n')
+        .map(|line| highlight_line_with(line, reg, highlight_style))
+        .collect()
+}
+
+/// Creates `Text` like [`highlight_text`], but matches the pattern against logical lines, where
+/// a line ending with `continuation` (like a `\\` in shell scripts) continues on the next one.
+///
+/// The physical lines of a logical line are joined without their `continuation` chars before
+/// matching, so a match can span several physical lines. The result is split back into the
+/// physical lines for rendering, a match spanning a join becoming highlighted `Spans` on each
+/// of the lines it covers. The `continuation` chars are kept in the output as plain `Spans`,
+/// since they aren't part of the matched text.
+///
+/// # Arguments
+///
+/// * `text` - A string slice that holds the text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+/// * `continuation` - The char marking a line that continues on the next one.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_text_continued;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = String::from("cargo build --rel\\\nease");
+///
+/// let expected_text = Text::from(vec![
+///     Line::from(vec![
+///         Span::from("cargo build "),
+///         Span::from("--rel").style(Style::new().bg(Color::Blue)),
+///         Span::from("\\"),
+///     ]),
+///     Line::from(vec![Span::from("ease").style(Style::new().bg(Color::Blue))]),
+/// ]);
+///
+/// assert_eq!(
+///     highlight_text_continued(text, r"--\w+", Style::new().bg(Color::Blue), '\\'),
+///     expected_text
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn highlight_text_continued<'a>(
+    text: String,
+    pattern: impl AsRef<str>,
+    highlight_style: Style,
+    continuation: char,
+) -> Text<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
 
-/// Creates a `Line` from the given `line` argument and adds `highlight_style` to `Spans` that match the pattern.
+    let mut highlighted_text = Text::default();
+    let mut lines = text.split_terminator('\n').peekable();
+
+    while lines.peek().is_some() {
+        // the physical lines of the logical line, without their continuation chars
+        let mut segments = Vec::new();
+        for line in lines.by_ref() {
+            match line.strip_suffix(continuation) {
+                Some(segment) => segments.push((segment, true)),
+                None => {
+                    segments.push((line, false));
+                    break;
+                }
+            }
+        }
+
+        let logical = segments
+            .iter()
+            .map(|(segment, _)| *segment)
+            .collect::<String>();
+        let matches = reg
+            .find_iter(&logical)
+            .filter(|m| !m.is_empty())
+            .map(|m| m.range())
+            .collect::<Vec<_>>();
+
+        let mut offset = 0;
+        for (segment, continued) in segments {
+            let end = offset + segment.len();
+            // the part of every match on this physical line, relative to its start
+            let clipped = matches
+                .iter()
+                .filter(|m| m.start.max(offset) < m.end.min(end))
+                .map(|m| {
+                    (
+                        m.start.max(offset) - offset..m.end.min(end) - offset,
+                        highlight_style,
+                    )
+                });
+
+            let mut highlighted_line = styled_line(segment, clipped);
+            if continued {
+                highlighted_line.push_span(Span::from(continuation.to_string()));
+            }
+
+            highlighted_text.push_line(highlighted_line);
+            offset = end;
+        }
+    }
+
+    highlighted_text
+}
+
+/// Creates `Text` from the given `text` argument and adds `highlight_style` to every occurrence
+/// of any of the given `words`, ignoring case.
+///
+/// The words are matched literally, so characters like `.` or `(` have no special meaning.
+/// When words overlap, the longest one starting at a given position wins and matches never
+/// overlap each other.
 ///
 /// # Arguments
 ///
-/// * `line` - A string slice that holds the line of text to be highlighted.
-/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `text` - A string slice that holds the text to be highlighted.
+/// * `words` - The literal words to be highlighted.
 /// * `highlight_style` - The style to be applied to the matching text.
+/// * `whole_word` - Only highlight words that are not part of a larger word (e.g. `is` won't
+///   be highlighted inside `this`). Word boundaries follow the regex `\b` rules.
 ///
 /// # Example
 ///
 /// ```
-/// use tui_pattern_highlighter::highlight_line;
+/// use tui_pattern_highlighter::highlight_literals_ci;
 /// use ratatui::{
 ///     style::{Color, Style},
-///     text::{Line, Span},
+///     text::{Line, Span, Text},
 /// };
 ///
-/// let line = String::from("Hi @buddy");
-/// let pattern = r"@\w+";
+/// let text = String::from("This IS a.b");
 /// let highlight_style = Style::new().bg(Color::Blue);
 ///
-/// let expected_line = Line::from(vec![
-///     Span::from("Hi "),
-///     Span::from("@buddy").style(Style::new().bg(Color::Blue)),
-/// ]);
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("This "),
+///     Span::from("IS").style(Style::new().bg(Color::Blue)),
+///     Span::from(" "),
+///     Span::from("a.b").style(Style::new().bg(Color::Blue)),
+/// ])]);
 ///
-/// assert_eq!(highlight_line(line, pattern, highlight_style), expected_line);
+/// assert_eq!(
+///     highlight_literals_ci(text, &["is", "a.b"], highlight_style, true),
+///     expected_text
+/// );
 /// ```
+#[cfg(feature = "regex")]
+pub fn highlight_literals_ci<'a>(
+    text: String,
+    words: &[&str],
+    highlight_style: Style,
+    whole_word: bool,
+) -> Text<'a> {
+    let mut words = words
+        .iter()
+        .filter(|word| !word.is_empty())
+        .map(|word| regex::escape(word))
+        .collect::<Vec<_>>();
+    words.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    words.dedup();
+
+    let pattern = if words.is_empty() {
+        // matches nothing
+        String::from(r"\b\B")
+    } else if whole_word {
+        format!(r"\b(?:{})\b", words.join("|"))
+    } else {
+        format!("(?:{})", words.join("|"))
+    };
+
+    let reg = RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .build()
+        .unwrap();
+    highlight_text_with(&text, &reg, highlight_style)
+}
+
+/// Creates `Text` from the given `text` argument and adds `highlight_style` to everything that
+/// any of the `patterns` matches.
 ///
-/// # Panics
+/// The matches of all patterns are merged, so overlapping or adjacent matches, from the same
+/// pattern or not, end up in a single `Span`. This shows everything a set of patterns touches
+/// when which pattern matched doesn't matter.
 ///
-/// The function may panic if the provided pattern is an invalid regular expression.
-pub fn highlight_line<'a>(
-    line: String,
-    pattern: impl AsRef<str>,
+/// # Arguments
+///
+/// * `text` - A string slice that holds the text to be highlighted.
+/// * `patterns` - The regular expression patterns to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_union;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = String::from("error: disk full, warning");
+///
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("error: disk").style(Style::new().bg(Color::Blue)),
+///     Span::from(" full, "),
+///     Span::from("warning").style(Style::new().bg(Color::Blue)),
+/// ])]);
+///
+/// assert_eq!(
+///     highlight_union(text, &["error", r"\w+: \w+", "warning"], Style::new().bg(Color::Blue)),
+///     Ok(expected_text)
+/// );
+/// ```
+///
+/// # Errors
+///
+/// All patterns are compiled before anything is highlighted, and the error of the first
+/// invalid one is returned.
+#[cfg(feature = "regex")]
+pub fn highlight_union<'a>(
+    text: String,
+    patterns: &[&str],
     highlight_style: Style,
-) -> Line<'a> {
-    let mut highlighted_line = Line::default();
+) -> Result<Text<'a>, regex::Error> {
+    let regs = patterns
+        .iter()
+        .map(|pattern| Regex::new(pattern))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let reg = Regex::new(pattern.as_ref()).unwrap();
-    let mut last_index = 0;
+    let mut highlighted_text = Text::default();
 
-    for m in reg.find_iter(&line).collect::<Vec<_>>() {
-        if m.start() > last_index {
-            highlighted_line.push_span(Span::from(line[last_index..m.start()].to_string()));
+    for line in text.split_terminator('\n') {
+        let mut ranges = regs
+            .iter()
+            .flat_map(|reg| reg.find_iter(line))
+            .filter(|m| !m.is_empty())
+            .map(|m| m.range())
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<usize>> = Vec::new();
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
         }
-        highlighted_line.push_span(Span::from(m.as_str().to_string()).style(highlight_style));
-        last_index = m.end();
-    }
 
-    if line.len() > last_index {
-        highlighted_line.push_span(Span::from(line[last_index..].to_string()));
+        highlighted_text.push_line(styled_line(
+            line,
+            merged.into_iter().map(|range| (range, highlight_style)),
+        ));
     }
 
-    highlighted_line
+    Ok(highlighted_text)
 }
 
-/// Creates `Text` from the given `line` argument and adds `highlight_style` to `Spans` that match the pattern.
-/// When the '\n' character is encountered, a new `Line` begins.
+/// Creates `Text` for a collapsible outline, where the lines matching `header_pattern` are
+/// headers styled with `header_style`, and the lines under a collapsed header are left out.
+///
+/// The children of a header are the lines after it, up to the next header. A header is
+/// collapsed when its index in `text` (counting lines from zero, like
+/// [`highlight_text`] splits them) is in `collapsed`; headers themselves are always shown, as
+/// are the lines before the first header. Indices of lines that aren't headers are ignored, and
+/// like everywhere else, a line with only empty matches isn't a header.
 ///
 /// # Arguments
 ///
-/// * `text` - A string slice that holds the text to be highlighted.
-/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
-/// * `highlight_style` - The style to be applied to the matching text.
+/// * `text` - A string slice that holds the text of the outline.
+/// * `header_pattern` - A regular expression pattern matching the header lines.
+/// * `collapsed` - The indices of the collapsed header lines.
+/// * `header_style` - The style to be applied to the header lines.
 ///
 /// # Example
 ///
 /// ```
-/// use tui_pattern_highlighter::highlight_text;
+/// use std::collections::HashSet;
+///
+/// use tui_pattern_highlighter::fold_by_pattern;
 /// use ratatui::{
 ///     style::{Color, Style},
-///     text::{Line, Span, Text},
+///     text::{Line, Text},
 /// };
 ///
-/// let text = String::from("Hi @buddy\n@stranger hello");
-/// let pattern = r"@\w+";
-/// let highlight_style = Style::new().bg(Color::Blue);
+/// let text = String::from("# a\n1\n2\n# b\n3");
+/// let header_style = Style::new().fg(Color::Blue);
 ///
 /// let expected_text = Text::from(vec![
-///     Line::from(vec![
-///         Span::from("Hi "),
-///         Span::from("@buddy").style(Style::new().bg(Color::Blue)),
-///     ]),
-///     Line::from(vec![
-///         Span::from("@stranger").style(Style::new().bg(Color::Blue)),
-///         Span::from(" hello"),
-///     ]),
+///     Line::from("# a").style(header_style),
+///     Line::from("# b").style(header_style),
+///     Line::from("3"),
 /// ]);
 ///
-/// assert_eq!(highlight_text(text, pattern, highlight_style), expected_text);
+/// assert_eq!(fold_by_pattern(text, "^#", &HashSet::from([0]), header_style), expected_text);
 /// ```
 ///
 /// # Panics
 ///
 /// The function may panic if the provided pattern is an invalid regular expression.
-pub fn highlight_text<'a>(
+#[cfg(feature = "regex")]
+pub fn fold_by_pattern<'a>(
     text: String,
-    pattern: impl AsRef<str>,
-    highlight_style: Style,
+    header_pattern: impl AsRef<str>,
+    collapsed: &HashSet<usize>,
+    header_style: Style,
 ) -> Text<'a> {
-    let mut highlighted_text = Text::default();
-
-    let mut last_index = 0;
+    let reg = Regex::new(header_pattern.as_ref()).unwrap();
 
-    for (i, _) in text.match_indices('\n') {
-        highlighted_text.push_line(highlight_line(
-            text[last_index..i].to_string(),
-            pattern.as_ref(),
-            highlight_style,
-        ));
-        last_index = i + 1;
-    }
+    let mut folded_text = Text::default();
+    let mut folding = false;
 
-    if text.len() > last_index {
-        highlighted_text.push_line(highlight_line(
-            text[last_index..].to_string(),
-            pattern,
-            highlight_style,
-        ));
+    for (i, line) in text.split_terminator('\n').enumerate() {
+        if reg.find_iter(line).any(|m| !m.is_empty()) {
+            folding = collapsed.contains(&i);
+            folded_text.push_line(Line::from(line.to_string()).style(header_style));
+        } else if !folding {
+            folded_text.push_line(Line::from(line.to_string()));
+        }
     }
 
-    highlighted_text
+    folded_text
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "regex"))]
 mod tests {
     use super::*;
     use ratatui::style::Color;
@@ -152,6 +1811,384 @@ mod tests {
         assert_eq!(returned_line, line);
     }
 
+    #[test]
+    fn highlighting_line_focus_test() {
+        let rest_style = Style::new().fg(Color::DarkGray);
+
+        let returned_line =
+            highlight_line_focus(TEXT[0..39].to_string(), r"@\w+", STYLE, rest_style);
+        let line = Line::from(vec![
+            Span::from("Hello ").style(rest_style),
+            Span::from("@Henry").style(STYLE),
+            Span::from(". Why are you named ").style(rest_style),
+            Span::from("@nobody").style(STYLE),
+        ]);
+        assert_eq!(returned_line, line);
+
+        let returned_line =
+            highlight_line_focus(TEXT[0..5].to_string(), r"@\w+", STYLE, rest_style);
+        assert_eq!(
+            returned_line,
+            Line::from(vec![Span::from("Hello").style(rest_style)])
+        );
+    }
+
+    #[test]
+    fn highlighting_line_range_test() {
+        let line = TEXT[0..39].to_string();
+
+        assert_eq!(
+            highlight_line_range(line.clone(), r"@\w+", 9..35, STYLE),
+            Line::from(vec![
+                Span::from("Hello @Henry. Why are you named "),
+                Span::from("@nobody").style(STYLE),
+            ])
+        );
+        assert_eq!(
+            highlight_line_range(line.clone(), r"@\w+", 0..7, STYLE),
+            Line::from(vec![
+                Span::from("Hello "),
+                Span::from("@Henry").style(STYLE),
+                Span::from(". Why are you named @nobody"),
+            ])
+        );
+        assert_eq!(
+            highlight_line_range(line.clone(), r"@\w+", 7..100, STYLE),
+            Line::from(vec![
+                Span::from("Hello @Henry. Why are you named "),
+                Span::from("@nobody").style(STYLE),
+            ])
+        );
+        assert_eq!(
+            highlight_line_range(line.clone(), r"o*", 0..39, STYLE),
+            highlight_line(line.clone(), r"o+", STYLE)
+        );
+        assert_eq!(
+            highlight_line_range(line.clone(), r"@\w+", 13..13, STYLE),
+            Line::from(vec![Span::from(line)])
+        );
+    }
+
+    #[test]
+    fn highlighting_line_with_selection_test() {
+        let selection_style = Style::new().bg(Color::Red);
+        let selected = STYLE.patch(selection_style);
+
+        assert_eq!(
+            highlight_line_with_selection(
+                TEXT[0..39].to_string(),
+                r"@\w+",
+                STYLE,
+                8..35,
+                selection_style
+            ),
+            Line::from(vec![
+                Span::from("Hello "),
+                Span::from("@H").style(STYLE),
+                Span::from("enry").style(selected),
+                Span::from(". Why are you named ").style(selection_style),
+                Span::from("@no").style(selected),
+                Span::from("body").style(STYLE),
+            ])
+        );
+        for selection in [0..0, 7..7, 50..60] {
+            assert_eq!(
+                highlight_line_with_selection(
+                    TEXT[0..39].to_string(),
+                    r"@\w+",
+                    STYLE,
+                    selection,
+                    selection_style
+                ),
+                highlight_line(TEXT[0..39].to_string(), r"@\w+", STYLE)
+            );
+        }
+        assert_eq!(
+            highlight_line_with_selection(
+                String::from("ab"),
+                r"@\w+",
+                STYLE,
+                1..9,
+                selection_style
+            ),
+            Line::from(vec![
+                Span::from("a"),
+                Span::from("b").style(selection_style)
+            ])
+        );
+    }
+
+    #[test]
+    fn highlighting_line_match_and_group_test() {
+        let group_style = Style::new().fg(Color::Red);
+        let patched = STYLE.fg(Color::Red);
+
+        assert_eq!(
+            highlight_line_match_and_group(
+                TEXT[0..39].to_string(),
+                r"@(\w)\w*",
+                STYLE,
+                1,
+                group_style
+            ),
+            Line::from(vec![
+                Span::from("Hello "),
+                Span::from("@").style(STYLE),
+                Span::from("H").style(patched),
+                Span::from("enry").style(STYLE),
+                Span::from(". Why are you named "),
+                Span::from("@").style(STYLE),
+                Span::from("n").style(patched),
+                Span::from("obody").style(STYLE),
+            ])
+        );
+        assert_eq!(
+            highlight_line_match_and_group(String::from("k=v k"), r"k(=v)?", STYLE, 1, group_style),
+            Line::from(vec![
+                Span::from("k").style(STYLE),
+                Span::from("=v").style(patched),
+                Span::from(" "),
+                Span::from("k").style(STYLE),
+            ])
+        );
+        assert_eq!(
+            highlight_line_match_and_group(TEXT[0..12].to_string(), r"@\w+", STYLE, 3, group_style),
+            highlight_line(TEXT[0..12].to_string(), r"@\w+", STYLE)
+        );
+    }
+
+    #[test]
+    fn highlighting_line_indexed_test() {
+        let mut seen = Vec::new();
+
+        let returned_line = highlight_line_indexed(TEXT[0..39].to_string(), r"@\w*", |i, m| {
+            seen.push((i, m.to_string()));
+            Style::new().bg(Color::Indexed(i as u8))
+        });
+
+        assert_eq!(
+            returned_line,
+            Line::from(vec![
+                Span::from("Hello "),
+                Span::from("@Henry").style(Style::new().bg(Color::Indexed(0))),
+                Span::from(". Why are you named "),
+                Span::from("@nobody").style(Style::new().bg(Color::Indexed(1))),
+            ])
+        );
+        assert_eq!(
+            seen,
+            vec![(0, String::from("@Henry")), (1, String::from("@nobody"))]
+        );
+    }
+
+    #[test]
+    fn highlighting_head_tail_test() {
+        let tail_style = Style::new().fg(Color::Red);
+
+        assert_eq!(
+            highlight_head_tail(TEXT[0..39].to_string(), r"@\w*|\b", STYLE, tail_style),
+            Line::from(vec![
+                Span::from("Hello "),
+                Span::from("@Henry").style(STYLE),
+                Span::from(". Why are you named @nobody").style(tail_style),
+            ])
+        );
+        assert_eq!(
+            highlight_head_tail(TEXT[0..39].to_string(), r"@nobody", STYLE, tail_style),
+            Line::from(vec![
+                Span::from("Hello @Henry. Why are you named "),
+                Span::from("@nobody").style(STYLE),
+            ])
+        );
+        assert_eq!(
+            highlight_head_tail(TEXT[0..39].to_string(), r"@\d", STYLE, tail_style),
+            Line::from(vec![Span::from(&TEXT[0..39])])
+        );
+        assert_eq!(
+            highlight_head_tail(String::new(), r"@\d", STYLE, tail_style),
+            Line::default()
+        );
+    }
+
+    #[test]
+    fn highlighting_line_pill_test() {
+        let body_style = Style::new().fg(Color::Red);
+
+        assert_eq!(
+            highlight_line_pill(
+                String::from("a é1 bc 🇵🇱xy👍🏽 e\u{301}"),
+                r"\S+|\b",
+                STYLE,
+                body_style
+            ),
+            Line::from(vec![
+                Span::from("a").style(STYLE),
+                Span::from(" "),
+                Span::from("é").style(STYLE),
+                Span::from("1").style(STYLE),
+                Span::from(" "),
+                Span::from("b").style(STYLE),
+                Span::from("c").style(STYLE),
+                Span::from(" "),
+                Span::from("🇵🇱").style(STYLE),
+                Span::from("xy").style(body_style),
+                Span::from("👍🏽").style(STYLE),
+                Span::from(" "),
+                Span::from("e\u{301}").style(STYLE),
+            ])
+        );
+    }
+
+    #[test]
+    fn center_on_match_test() {
+        let centered = |line: &str, width| {
+            let centered_line = center_on_match(line.to_string(), r"@\w+", width, STYLE);
+            assert_eq!(centered_line.width(), usize::from(width));
+            centered_line
+        };
+
+        assert_eq!(
+            centered("@a", 6),
+            Line::from(vec![
+                Span::from("  "),
+                Span::from("@a").style(STYLE),
+                Span::from("  ")
+            ])
+        );
+        assert_eq!(
+            centered("żółw żółw @a b", 9),
+            Line::from(vec![
+                Span::from("…w "),
+                Span::from("@a").style(STYLE),
+                Span::from(" b  "),
+            ])
+        );
+        assert_eq!(
+            centered("日本語 @a 日本語", 9),
+            Line::from(vec![
+                Span::from(" … "),
+                Span::from("@a").style(STYLE),
+                Span::from(" 日…"),
+            ])
+        );
+        assert_eq!(
+            centered("x @abcdef y", 4),
+            Line::from(vec![Span::from("@ab…").style(STYLE)])
+        );
+        assert_eq!(
+            centered("no match here", 6),
+            Line::from(vec![Span::from("no ma…")])
+        );
+        assert_eq!(centered("", 3), Line::from(vec![Span::from("   ")]));
+        assert_eq!(centered("@a", 0), Line::default());
+    }
+
+    #[test]
+    fn highlighting_line_suffix_test() {
+        let suffix = |line: &str, delimiter| {
+            highlight_line_suffix(line.to_string(), r"[\w:.]+", delimiter, STYLE)
+        };
+
+        assert_eq!(
+            suffix("a::b::c d e::", "::"),
+            Line::from(vec![
+                Span::from("a::b::"),
+                Span::from("c").style(STYLE),
+                Span::from(" "),
+                Span::from("d").style(STYLE),
+                Span::from(" e::"),
+            ])
+        );
+        assert_eq!(
+            suffix("żółw.ćma x", "."),
+            Line::from(vec![
+                Span::from("żółw."),
+                Span::from("ćma").style(STYLE),
+                Span::from(" "),
+                Span::from("x").style(STYLE),
+            ])
+        );
+        assert_eq!(
+            suffix("a::b c", ""),
+            highlight_line(String::from("a::b c"), r"[\w:.]+", STYLE)
+        );
+    }
+
+    #[test]
+    fn highlighting_line_kv_test() {
+        let mut seen = Vec::new();
+
+        let returned_line = highlight_line_kv(
+            String::from("a=1 =2 b= c=3"),
+            r"(\w+)?=(\d*)",
+            |key, value| {
+                seen.push((key.to_string(), value.to_string()));
+                if key == "a" {
+                    STYLE
+                } else {
+                    Style::default()
+                }
+            },
+        );
+
+        assert_eq!(
+            returned_line,
+            Line::from(vec![
+                Span::from("a="),
+                Span::from("1").style(STYLE),
+                Span::from(" ="),
+                Span::from("2"),
+                Span::from(" b= c="),
+                Span::from("3"),
+            ])
+        );
+        assert_eq!(
+            seen,
+            vec![
+                (String::from("a"), String::from("1")),
+                (String::new(), String::from("2")),
+                (String::from("c"), String::from("3")),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exactly two capture groups")]
+    fn highlighting_line_kv_arity_test() {
+        highlight_line_kv(String::from("a=1"), r"(\w+)=\w+", |_, _| STYLE);
+    }
+
+    #[test]
+    fn highlighting_line_build_test() {
+        let mut id = 0;
+
+        let returned_line =
+            highlight_line_build(TEXT[0..39].to_string(), r"@\w*", |text, is_match| {
+                if is_match {
+                    id += 1;
+                    Span::from(format!("{text}#{id}")).style(STYLE)
+                } else {
+                    Span::from(text.to_string())
+                }
+            });
+
+        assert_eq!(
+            returned_line,
+            Line::from(vec![
+                Span::from("Hello "),
+                Span::from("@Henry#1").style(STYLE),
+                Span::from(". Why are you named "),
+                Span::from("@nobody#2").style(STYLE),
+            ])
+        );
+        assert_eq!(
+            highlight_line_build(TEXT[0..39].to_string(), r"@\w*", |text, is_match| {
+                Span::from(text.to_string()).style(if is_match { STYLE } else { Style::new() })
+            }),
+            highlight_line(TEXT[0..39].to_string(), r"@\w*", STYLE)
+        );
+    }
+
     #[test]
     fn highlighting_text_test() {
         let returned_text = highlight_text(TEXT.to_string(), r"@\w+", STYLE);
@@ -173,4 +2210,328 @@ mod tests {
 
         assert_eq!(returned_text, text);
     }
+
+    #[test]
+    fn highlighting_literals_ci_test() {
+        let text = String::from("This is it.\nIT'S (it)");
+
+        let substrings =
+            highlight_literals_ci(text.clone(), &["is", "it", "(it)", ""], STYLE, false);
+        assert_eq!(
+            substrings,
+            Text::from(vec![
+                Line::from(vec![
+                    Span::from("Th"),
+                    Span::from("is").style(STYLE),
+                    Span::from(" "),
+                    Span::from("is").style(STYLE),
+                    Span::from(" "),
+                    Span::from("it").style(STYLE),
+                    Span::from("."),
+                ]),
+                Line::from(vec![
+                    Span::from("IT").style(STYLE),
+                    Span::from("'S "),
+                    Span::from("(it)").style(STYLE),
+                ]),
+            ])
+        );
+
+        let whole_words = highlight_literals_ci(text.clone(), &["is"], STYLE, true);
+        assert_eq!(
+            whole_words.lines[0],
+            Line::from(vec![
+                Span::from("This "),
+                Span::from("is").style(STYLE),
+                Span::from(" it."),
+            ])
+        );
+
+        let nothing = highlight_literals_ci(text.clone(), &[], STYLE, false);
+        assert_eq!(nothing, highlight_text(text, r"\b\B", STYLE));
+    }
+
+    #[test]
+    fn highlighting_text_dedup_test() {
+        let repeat = Style::new().fg(Color::Red);
+        let text = String::from("@a @b @a\n\n@A @b|\n@a");
+
+        assert_eq!(
+            highlight_text_dedup(text.clone(), r"@\w+|\|?", STYLE, repeat),
+            Text::from(vec![
+                Line::from(vec![
+                    Span::from("@a").style(STYLE),
+                    Span::from(" "),
+                    Span::from("@b").style(STYLE),
+                    Span::from(" "),
+                    Span::from("@a").style(repeat),
+                ]),
+                Line::default(),
+                Line::from(vec![
+                    Span::from("@A").style(STYLE),
+                    Span::from(" "),
+                    Span::from("@b").style(repeat),
+                    Span::from("|").style(STYLE),
+                ]),
+                Line::from(vec![Span::from("@a").style(repeat)]),
+            ])
+        );
+        assert_eq!(
+            highlight_text_dedup(text.clone(), r"@\w+", STYLE, STYLE),
+            highlight_text(text, r"@\w+", STYLE)
+        );
+    }
+
+    #[test]
+    fn highlighting_text_lines_with_test() {
+        let error_style = Style::new().bg(Color::Red);
+        let mut seen = Vec::new();
+
+        let returned_text = highlight_text_lines_with(TEXT.to_string(), r"@\w+", STYLE, |line| {
+            seen.push(line.to_string());
+            if line.contains("Bill") {
+                error_style
+            } else {
+                Style::new()
+            }
+        });
+
+        let mut text = highlight_text(TEXT.to_string(), r"@\w+", STYLE);
+        text.lines[1] = text.lines[1].clone().style(error_style);
+
+        assert_eq!(returned_text, text);
+        assert_eq!(seen, TEXT.split('\n').collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn highlighting_text_borrowed_test() {
+        let reg = Regex::new(r"@\w+").unwrap();
+        let returned_text = highlight_text_borrowed(TEXT, &reg, STYLE);
+
+        assert_eq!(
+            returned_text,
+            highlight_text(TEXT.to_string(), r"@\w+", STYLE)
+        );
+        assert!(returned_text
+            .lines
+            .iter()
+            .flat_map(|line| &line.spans)
+            .all(|span| matches!(span.content, std::borrow::Cow::Borrowed(_))));
+    }
+
+    #[cfg(feature = "macros")]
+    #[test]
+    fn static_highlighter_test() {
+        let reg: &'static Regex = static_highlighter_in_fn();
+
+        assert!(std::ptr::eq(reg, static_highlighter_in_fn()));
+        assert_eq!(
+            highlight_text_borrowed(TEXT, reg, STYLE),
+            highlight_text(TEXT.to_string(), r"@\w+", STYLE)
+        );
+    }
+
+    #[cfg(feature = "macros")]
+    fn static_highlighter_in_fn() -> &'static Regex {
+        static_highlighter!(r"@\w+")
+    }
+
+    #[test]
+    fn highlighting_line_with_width_test() {
+        let reg = Regex::new(r"@\w+").unwrap();
+
+        for (line, width) in [
+            ("", 0),
+            ("@a b", 4),
+            ("żółw @b", 7),
+            ("e\u{301}@c", 3),
+            ("日本語 @日本", 12),
+        ] {
+            let (highlighted_line, returned_width) = highlight_line_with_width(line, &reg, STYLE);
+
+            assert_eq!(
+                highlighted_line,
+                highlight_line(line.to_string(), r"@\w+", STYLE)
+            );
+            assert_eq!(returned_width, width);
+            assert_eq!(usize::from(returned_width), highlighted_line.width());
+        }
+
+        let long_line = "x".repeat(70_000);
+        assert_eq!(
+            highlight_line_with_width(&long_line, &reg, STYLE).1,
+            u16::MAX
+        );
+    }
+
+    #[test]
+    fn highlighting_viewport_test() {
+        let reg = Regex::new(r"@\w+").unwrap();
+        let text = "@a\nb\n\n@d @e\nf\n";
+        let lines = highlight_text_borrowed(text, &reg, STYLE).lines;
+
+        assert_eq!(
+            highlight_viewport(text, &reg, STYLE, 0, 2),
+            Text::from(lines[..2].to_vec())
+        );
+        assert_eq!(
+            highlight_viewport(text, &reg, STYLE, 2, 10),
+            Text::from(lines[2..].to_vec())
+        );
+        assert_eq!(highlight_viewport(text, &reg, STYLE, 1, 0), Text::default());
+        assert_eq!(highlight_viewport(text, &reg, STYLE, 5, 3), Text::default());
+    }
+
+    #[test]
+    fn highlighting_union_test() {
+        assert_eq!(
+            highlight_union(
+                TEXT.to_string(),
+                &[r"@\w+", r"\w+ @", r"\bis\b", r"\b"],
+                STYLE
+            ),
+            Ok(Text::from(vec![
+                Line::from(vec![
+                    Span::from("Hello @Henry").style(STYLE),
+                    Span::from(". Why are you "),
+                    Span::from("named @nobody").style(STYLE),
+                ]),
+                Line::from(vec![
+                    Span::from("Because yes, and "),
+                    Span::from("you @John").style(STYLE),
+                    Span::from(". Btw "),
+                    Span::from("Where @Bill").style(STYLE),
+                    Span::from(" "),
+                    Span::from("is").style(STYLE),
+                    Span::from(" ?"),
+                ]),
+            ]))
+        );
+        assert_eq!(
+            highlight_union(TEXT.to_string(), &[], STYLE),
+            Ok(highlight_text(TEXT.to_string(), r"\b\B", STYLE))
+        );
+        assert!(
+            highlight_union(TEXT.to_string(), &[r"@\w+", "(", "["], STYLE)
+                .is_err_and(|err| err.to_string().contains("("))
+        );
+    }
+
+    #[test]
+    fn highlighting_text_rev_test() {
+        let reg = Regex::new(r"@\w+").unwrap();
+
+        for text in [TEXT, "", "\n", "\n\n@a\n", "a\n\n@b"] {
+            let mut lines = highlight_text_borrowed(text, &reg, STYLE).lines;
+            lines.reverse();
+
+            assert_eq!(
+                highlight_text_rev(text, &reg, STYLE).collect::<Vec<_>>(),
+                lines
+            );
+        }
+    }
+
+    #[test]
+    fn highlighting_text_continued_test() {
+        assert_eq!(
+            highlight_text_continued(
+                String::from("a @b\\\nc\\\n\\\nd e\n@f\\"),
+                r"@[\w ]+",
+                STYLE,
+                '\\'
+            ),
+            Text::from(vec![
+                Line::from(vec![
+                    Span::from("a "),
+                    Span::from("@b").style(STYLE),
+                    Span::from("\\"),
+                ]),
+                Line::from(vec![Span::from("c").style(STYLE), Span::from("\\")]),
+                Line::from(vec![Span::from("\\")]),
+                Line::from(vec![Span::from("d e").style(STYLE)]),
+                Line::from(vec![Span::from("@f").style(STYLE), Span::from("\\")]),
+            ])
+        );
+        assert_eq!(
+            highlight_text_continued(TEXT.to_string(), r"@\w+", STYLE, '\\'),
+            highlight_text(TEXT.to_string(), r"@\w+", STYLE)
+        );
+    }
+
+    #[test]
+    fn fold_by_pattern_test() {
+        let text = String::from("intro\n# a\n1\n\n# b\n2\n# c\n3\n4");
+        let fold = |collapsed: &[usize]| {
+            fold_by_pattern(
+                text.clone(),
+                r"^#|\b",
+                &collapsed.iter().copied().collect(),
+                STYLE,
+            )
+            .lines
+            .into_iter()
+            .map(|line| (line.to_string(), line.style == STYLE))
+            .collect::<Vec<_>>()
+        };
+        let line = |content: &str, header| (content.to_string(), header);
+
+        assert_eq!(
+            fold(&[]),
+            vec![
+                line("intro", false),
+                line("# a", true),
+                line("1", false),
+                line("", false),
+                line("# b", true),
+                line("2", false),
+                line("# c", true),
+                line("3", false),
+                line("4", false),
+            ]
+        );
+        assert_eq!(
+            fold(&[1, 6, 0, 2]),
+            vec![
+                line("intro", false),
+                line("# a", true),
+                line("# b", true),
+                line("2", false),
+                line("# c", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn highlight_macro_test() {
+        let other = Style::new().fg(Color::Red);
+        let mention = r"@\w+";
+
+        assert_eq!(
+            highlight!("@a b\nc", r"@\w+" => STYLE),
+            highlight_text(String::from("@a b\nc"), r"@\w+", STYLE)
+        );
+        assert_eq!(
+            highlight!(
+                String::from("@a #b 1"),
+                mention => STYLE,
+                (format!("#{}", r"\w+")) => other,
+                r"\d" => if true { other.bg(Color::Black) } else { other },
+            ),
+            Text::from(vec![Line::from(vec![
+                Span::from("@a").style(STYLE),
+                Span::from(" "),
+                Span::from("#b").style(other),
+                Span::from(" "),
+                Span::from("1").style(other.bg(Color::Black)),
+            ])])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid pattern for rule `(`")]
+    fn highlight_macro_invalid_pattern_test() {
+        let pattern = "(";
+        highlight!("a", pattern => STYLE);
+    }
 }
@@ -0,0 +1,285 @@
+use std::{iter, ops::Range};
+
+use ratatui::{
+    style::Style,
+    text::{Line, Text},
+};
+
+use crate::segments::styled_line;
+
+/// Highlights one or more literal strings without using regular expressions.
+///
+/// This is the lightweight alternative to [`Highlighter`](crate::Highlighter) for apps that
+/// only ever highlight fixed strings: with the `literal` feature enabled and default features
+/// turned off, the crate doesn't depend on `regex` at all. The `Lines` and `Text` it returns
+/// have exactly the same shape as the ones produced by the regex based functions for the
+/// equivalent escaped pattern, so the two can be swapped freely.
+///
+/// Like an alternation of the literals, matches never overlap and, when several literals match at
+/// the same position, the longest one wins. With whole word matching, the literals matching at a
+/// position are tried longest first until one of them is a whole word, so `"is"` still matches at
+/// the start of `"is itx"` when `"is it"` doesn't. Case-insensitive matching compares the text char
+/// by char using simple lowercase folding (so e.g. `ß` doesn't match `ss`), and whole word matching
+/// only accepts matches that aren't surrounded by alphanumeric characters or `_`.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::LiteralHighlighter;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let highlighter = LiteralHighlighter::new(["todo", "fixme"], Style::new().bg(Color::Blue))
+///     .case_insensitive(true)
+///     .whole_word(true);
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("TODO").style(Style::new().bg(Color::Blue)),
+///     Span::from(": todos"),
+/// ]);
+///
+/// assert_eq!(highlighter.highlight_line(String::from("TODO: todos")), expected_line);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LiteralHighlighter {
+    literals: Vec<String>,
+    style: Style,
+    case_insensitive: bool,
+    whole_word: bool,
+}
+
+impl LiteralHighlighter {
+    /// Creates a new `LiteralHighlighter` that applies `highlight_style` to every occurrence of
+    /// any of the `literals`. Empty literals are ignored.
+    pub fn new<I, S>(literals: I, highlight_style: Style) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut literals = literals
+            .into_iter()
+            .map(Into::into)
+            .filter(|literal| !literal.is_empty())
+            .collect::<Vec<_>>();
+        literals.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        literals.dedup();
+
+        Self {
+            literals,
+            style: highlight_style,
+            case_insensitive: false,
+            whole_word: false,
+        }
+    }
+
+    /// Ignores case when matching. It's off by default.
+    pub fn case_insensitive(self, case_insensitive: bool) -> Self {
+        Self {
+            case_insensitive,
+            ..self
+        }
+    }
+
+    /// Only matches literals that aren't part of a larger word. It's off by default.
+    pub fn whole_word(self, whole_word: bool) -> Self {
+        Self { whole_word, ..self }
+    }
+
+    /// Creates a `Line` like [`highlight_line`](crate::highlight_line).
+    pub fn highlight_line<'a>(&self, line: String) -> Line<'a> {
+        self.line(&line)
+    }
+
+    /// Creates `Text` like [`highlight_text`](crate::highlight_text).
+    pub fn highlight_text<'a>(&self, text: String) -> Text<'a> {
+        text.split_terminator('\n')
+            .map(|line| self.line(line))
+            .collect()
+    }
+
+    fn line<'a>(&self, line: &str) -> Line<'a> {
+        let mut next_starts = if self.case_insensitive {
+            Vec::new()
+        } else {
+            self.literals
+                .iter()
+                .map(|literal| line.find(literal.as_str()))
+                .collect()
+        };
+
+        let mut at = 0;
+        let matches = iter::from_fn(|| {
+            let m = if self.case_insensitive {
+                self.find_folded(line, at)
+            } else {
+                self.find_exact(line, at, &mut next_starts)
+            }?;
+            at = m.end;
+            Some((m, self.style))
+        });
+
+        styled_line(line, matches)
+    }
+
+    /// Finds the leftmost accepted match at or after `at`.
+    ///
+    /// `next_starts` holds the start of the next occurrence of every literal, like the state of
+    /// a regex iterator, so a literal is only searched for again once the search has moved past
+    /// its next occurrence.
+    fn find_exact(
+        &self,
+        haystack: &str,
+        mut at: usize,
+        next_starts: &mut [Option<usize>],
+    ) -> Option<Range<usize>> {
+        loop {
+            for (literal, next_start) in self.literals.iter().zip(next_starts.iter_mut()) {
+                if next_start.is_some_and(|start| start < at) {
+                    *next_start = haystack[at..]
+                        .find(literal.as_str())
+                        .map(|start| at + start);
+                }
+            }
+
+            let start = next_starts.iter().flatten().min().copied()?;
+            // literals are sorted longest first, so the first accepted one is the longest
+            let m = self
+                .literals
+                .iter()
+                .zip(next_starts.iter())
+                .filter(|(_, next_start)| **next_start == Some(start))
+                .map(|(literal, _)| start..start + literal.len())
+                .find(|m| self.accepts(haystack, m));
+            if m.is_some() {
+                return m;
+            }
+
+            at = start + haystack[start..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    fn find_folded(&self, haystack: &str, at: usize) -> Option<Range<usize>> {
+        haystack[at..].char_indices().find_map(|(i, _)| {
+            let start = at + i;
+            self.literals
+                .iter()
+                .filter_map(|literal| {
+                    let len = folded_prefix_len(&haystack[start..], literal)?;
+                    Some(start..start + len)
+                })
+                .find(|m| self.accepts(haystack, m))
+        })
+    }
+
+    fn accepts(&self, haystack: &str, m: &Range<usize>) -> bool {
+        !self.whole_word || is_whole_word(haystack, m)
+    }
+}
+
+fn folded_prefix_len(haystack: &str, literal: &str) -> Option<usize> {
+    let mut haystack_chars = haystack.char_indices();
+
+    for l in literal.chars() {
+        let (_, h) = haystack_chars.next()?;
+        if h != l && !h.to_lowercase().eq(l.to_lowercase()) {
+            return None;
+        }
+    }
+
+    Some(haystack_chars.next().map_or(haystack.len(), |(i, _)| i))
+}
+
+fn is_whole_word(haystack: &str, m: &Range<usize>) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    !haystack[..m.start].chars().next_back().is_some_and(is_word)
+        && !haystack[m.end..].chars().next().is_some_and(is_word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{style::Color, text::Span};
+
+    const STYLE: Style = Style::new().bg(Color::Blue);
+    const TEXT: &str = "This is it, IS IT?\nitself Straße";
+
+    #[test]
+    fn literal_highlighter_test() {
+        let highlighter = LiteralHighlighter::new(["is", "it", "", "is it"], STYLE);
+
+        assert_eq!(
+            highlighter.highlight_line(TEXT[..18].to_string()),
+            Line::from(vec![
+                Span::from("Th"),
+                Span::from("is").style(STYLE),
+                Span::from(" "),
+                Span::from("is it").style(STYLE),
+                Span::from(", IS IT?"),
+            ])
+        );
+        assert_eq!(
+            LiteralHighlighter::new(Vec::<String>::new(), STYLE).highlight_line(String::from("a")),
+            Line::from(vec![Span::from("a")])
+        );
+    }
+
+    #[test]
+    fn literal_highlighter_modes_test() {
+        let highlighter = LiteralHighlighter::new(["it", "STRASSE", "straße"], STYLE)
+            .case_insensitive(true)
+            .whole_word(true);
+
+        assert_eq!(
+            highlighter.highlight_text(TEXT.to_string()),
+            Text::from(vec![
+                Line::from(vec![
+                    Span::from("This is "),
+                    Span::from("it").style(STYLE),
+                    Span::from(", IS "),
+                    Span::from("IT").style(STYLE),
+                    Span::from("?"),
+                ]),
+                Line::from(vec![
+                    Span::from("itself "),
+                    Span::from("Straße").style(STYLE),
+                ]),
+            ])
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn literal_highlighter_matches_regex_test() {
+        use crate::highlight_literals_ci;
+
+        let text = "This is it a.b (x)\n\nIT'S a (X)";
+
+        // `\b` only agrees with the whole word check for literals made of word characters
+        for (text, words, whole_word) in [
+            (text, &["is", "it", "a.", "(x)"][..], false),
+            (text, &["is", "it", "a"], true),
+            ("is itx", &["is it", "is"], true),
+            ("is itx is it\nisis it", &["is it", "is", "it"], true),
+            ("aaa aa", &["aa", "a"], false),
+        ] {
+            let highlighter =
+                LiteralHighlighter::new(words.iter().copied(), STYLE).whole_word(whole_word);
+            let expected = highlight_literals_ci(text.to_string(), words, STYLE, whole_word);
+
+            assert_eq!(
+                highlighter
+                    .clone()
+                    .case_insensitive(true)
+                    .highlight_text(text.to_string()),
+                expected
+            );
+            // matching exactly only agrees with the case-insensitive regex on lowercase text
+            if !text.contains('T') {
+                assert_eq!(highlighter.highlight_text(text.to_string()), expected);
+            }
+        }
+    }
+}
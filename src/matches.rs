@@ -0,0 +1,361 @@
+use std::ops::Range;
+
+use ratatui::{
+    style::Style,
+    text::{Line, Span, Text},
+};
+use regex::Regex;
+
+use crate::segments::fill_gaps;
+
+/// Describes where a single match was found.
+///
+/// All ranges are relative to the start of the line the match was found on, not to the start
+/// of the whole text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchInfo {
+    /// The zero-based index of the line containing the match.
+    pub line: usize,
+    /// The range of bytes covered by the match.
+    pub byte_range: Range<usize>,
+    /// The range of chars covered by the match.
+    pub char_range: Range<usize>,
+}
+
+/// Creates `Text` like [`highlight_text`](crate::highlight_text) and also returns the positions
+/// of every match, running the regular expression only once.
+///
+/// The returned `Text` and metadata are guaranteed to be consistent: the n-th `MatchInfo`
+/// describes the n-th highlighted `Span`, and its `line` is the index of the `Line` in the
+/// returned `Text` that holds it.
+///
+/// # Arguments
+///
+/// * `text` - A string slice that holds the text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{highlight_text_with_meta, MatchInfo};
+/// use ratatui::style::{Color, Style};
+///
+/// let text = String::from("Hi @buddy\nżółw @stranger");
+/// let (_, matches) = highlight_text_with_meta(text, r"@\w+", Style::new().bg(Color::Blue));
+///
+/// assert_eq!(
+///     matches,
+///     vec![
+///         MatchInfo { line: 0, byte_range: 3..9, char_range: 3..9 },
+///         MatchInfo { line: 1, byte_range: 8..17, char_range: 5..14 },
+///     ]
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+pub fn highlight_text_with_meta<'a>(
+    text: String,
+    pattern: impl AsRef<str>,
+    highlight_style: Style,
+) -> (Text<'a>, Vec<MatchInfo>) {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+
+    let mut highlighted_text = Text::default();
+    let mut matches = Vec::new();
+
+    for (line_index, line) in text.split_terminator('\n').enumerate() {
+        let mut highlighted_line = Line::default();
+        let mut last_char = 0;
+
+        for (range, m) in fill_gaps(line.len(), reg.find_iter(line)) {
+            let span = Span::from(line[range.clone()].to_string());
+            let end_char = last_char + span.content.chars().count();
+
+            if m.is_some() {
+                highlighted_line.push_span(span.style(highlight_style));
+                matches.push(MatchInfo {
+                    line: line_index,
+                    byte_range: range,
+                    char_range: last_char..end_char,
+                });
+            } else {
+                highlighted_line.push_span(span);
+            }
+            last_char = end_char;
+        }
+
+        highlighted_text.push_line(highlighted_line);
+    }
+
+    (highlighted_text, matches)
+}
+
+/// Creates `Text` like [`highlight_text`](crate::highlight_text) and splits it in two, right
+/// before the line holding the `n`-th match (counting from zero).
+///
+/// The first `Text` holds the lines before that line, and the second one the line itself and
+/// everything after it, which is handy for pinning a match to the top of a pane. If there are
+/// fewer than `n + 1` matches, the first `Text` holds everything and the second one is empty.
+///
+/// # Arguments
+///
+/// * `text` - A string slice that holds the text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+/// * `n` - The index of the match to split at.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::split_at_match;
+/// use ratatui::style::Style;
+///
+/// let text = String::from("@a\nb\n@c @d\ne");
+/// let (before, after) = split_at_match(text, r"@\w+", Style::new(), 2);
+///
+/// assert_eq!(before.lines.len(), 2);
+/// assert_eq!(after.lines.len(), 2);
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+pub fn split_at_match<'a>(
+    text: String,
+    pattern: impl AsRef<str>,
+    highlight_style: Style,
+    n: usize,
+) -> (Text<'a>, Text<'a>) {
+    let (mut before, matches) = highlight_text_with_meta(text, pattern, highlight_style);
+
+    let after = match matches.get(n) {
+        Some(m) => Text::from(before.lines.split_off(m.line)),
+        None => Text::default(),
+    };
+
+    (before, after)
+}
+
+/// Creates a `Line` like [`highlight_line`](crate::highlight_line), along with the byte range
+/// of `line` each of its `Spans` comes from, e.g. to map a clicked column back to an offset.
+///
+/// The ranges are parallel to the `Spans`, so there are exactly as many ranges as `Spans`, and
+/// they partition `line` in order: the first one starts at `0`, each one starts where the
+/// previous one ends, and the last one ends at `line.len()`.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `pattern` - A regular expression pattern to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_line_mapped;
+/// use ratatui::style::{Color, Style};
+///
+/// let (line, ranges) =
+///     highlight_line_mapped(String::from("Hi @buddy!"), r"@\w+", Style::new().bg(Color::Blue));
+///
+/// assert_eq!(line.spans.len(), ranges.len());
+/// assert_eq!(ranges, vec![0..3, 3..9, 9..10]);
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+pub fn highlight_line_mapped<'a>(
+    line: String,
+    pattern: impl AsRef<str>,
+    highlight_style: Style,
+) -> (Line<'a>, Vec<Range<usize>>) {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+
+    let (spans, ranges): (Vec<_>, Vec<_>) = fill_gaps(line.len(), reg.find_iter(&line))
+        .map(|(range, m)| {
+            let span = Span::from(line[range.clone()].to_string());
+            match m {
+                Some(_) => (span.style(highlight_style), range),
+                None => (span, range),
+            }
+        })
+        .unzip();
+
+    (Line::from(spans), ranges)
+}
+
+/// Checks whether `pattern` matches anywhere in `text`, without highlighting anything.
+///
+/// `text` is searched line by line, like [`highlight_text`](crate::highlight_text) does, so the
+/// answer is the same as whether highlighting it would style anything, ignoring empty matches.
+/// The search stops at the first match, which makes this the cheapest way to decide whether a
+/// buffer is worth highlighting at all.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::matches_any;
+///
+/// assert_eq!(matches_any("Hi\n@buddy", r"@\w+"), Ok(true));
+/// assert_eq!(matches_any("Hi\nbuddy", r"@\w+"), Ok(false));
+/// assert!(matches_any("Hi", r"(").is_err());
+/// ```
+pub fn matches_any(text: &str, pattern: impl AsRef<str>) -> Result<bool, regex::Error> {
+    let reg = Regex::new(pattern.as_ref())?;
+
+    Ok(any_line_matches(text.split_terminator('\n'), &reg))
+}
+
+/// Checks whether `reg` has a non-empty match in any of `lines`, without looking at the lines
+/// after the first one that does.
+fn any_line_matches<'h>(mut lines: impl Iterator<Item = &'h str>, reg: &Regex) -> bool {
+    lines.any(|line| reg.find_iter(line).any(|m| !m.is_empty()))
+}
+
+/// Counts the lines of `text` where `pattern` matches, e.g. for a "found on 12 of 300 lines"
+/// status readout next to the output of [`highlight_text`](crate::highlight_text).
+///
+/// A line counts once however many matches it has. `text` is split into lines like
+/// [`highlight_text`](crate::highlight_text) does, and empty matches are ignored the same way,
+/// so the count is the number of returned `Lines` that hold a highlighted `Span`.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::count_matching_lines;
+///
+/// assert_eq!(count_matching_lines("@a @b\nc\n@d\n", r"@\w+"), Ok(2));
+/// assert!(count_matching_lines("a", r"(").is_err());
+/// ```
+pub fn count_matching_lines(text: &str, pattern: impl AsRef<str>) -> Result<usize, regex::Error> {
+    let reg = Regex::new(pattern.as_ref())?;
+
+    Ok(text
+        .split_terminator('\n')
+        .filter(|line| reg.find_iter(line).any(|m| !m.is_empty()))
+        .count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::highlight_text;
+    use ratatui::style::Color;
+
+    const STYLE: Style = Style::new().bg(Color::Blue);
+
+    #[test]
+    fn highlighting_text_with_meta_test() {
+        let text = String::from("@a b @c\n\nć @d");
+        let (returned_text, matches) = highlight_text_with_meta(text.clone(), r"@\w+", STYLE);
+
+        assert_eq!(returned_text, highlight_text(text, r"@\w+", STYLE));
+        assert_eq!(
+            matches,
+            vec![
+                MatchInfo {
+                    line: 0,
+                    byte_range: 0..2,
+                    char_range: 0..2,
+                },
+                MatchInfo {
+                    line: 0,
+                    byte_range: 5..7,
+                    char_range: 5..7,
+                },
+                MatchInfo {
+                    line: 2,
+                    byte_range: 3..5,
+                    char_range: 2..4,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn splitting_at_match_test() {
+        let text = String::from("@a\nb\n@c @d\ne");
+        let lines = highlight_text(text.clone(), r"@\w+", STYLE).lines;
+
+        let (before, after) = split_at_match(text.clone(), r"@\w+", STYLE, 0);
+        assert_eq!(before, Text::default());
+        assert_eq!(after, Text::from(lines.clone()));
+
+        let (before, after) = split_at_match(text.clone(), r"@\w+", STYLE, 2);
+        assert_eq!(before, Text::from(lines[..2].to_vec()));
+        assert_eq!(after, Text::from(lines[2..].to_vec()));
+
+        let (before, after) = split_at_match(text, r"@\w+", STYLE, 3);
+        assert_eq!(before, Text::from(lines));
+        assert_eq!(after, Text::default());
+    }
+
+    #[test]
+    fn matches_any_test() {
+        let text = "Hi @buddy\nhello @stranger";
+
+        assert_eq!(matches_any(text, r"@\w+"), Ok(true));
+        assert_eq!(matches_any(text, r"@\d+"), Ok(false));
+        assert_eq!(matches_any(text, r"\b"), Ok(false));
+        assert_eq!(matches_any(text, r"buddy\nhello"), Ok(false));
+        assert_eq!(matches_any("", r"."), Ok(false));
+    }
+
+    #[test]
+    fn count_matching_lines_test() {
+        let text = "@a @b\n\nc @d\n@\n";
+        let highlighted_text = highlight_text(text.to_string(), r"@\w+|\b", STYLE);
+
+        assert_eq!(count_matching_lines(text, r"@\w+|\b"), Ok(2));
+        assert_eq!(
+            highlighted_text
+                .lines
+                .iter()
+                .filter(|line| line.spans.iter().any(|span| span.style == STYLE))
+                .count(),
+            2
+        );
+        assert_eq!(count_matching_lines(text, r"\b"), Ok(0));
+        assert_eq!(count_matching_lines("", r"."), Ok(0));
+    }
+
+    #[test]
+    fn matches_any_stops_at_first_hit_test() {
+        let reg = Regex::new(r"@\w+").unwrap();
+        let text = format!("a\nb @c\n{}", "@d\n".repeat(1_000));
+
+        let mut scanned = 0;
+        let lines = text.split_terminator('\n').inspect(|_| scanned += 1);
+        assert!(any_line_matches(lines, &reg));
+        assert_eq!(scanned, 2);
+
+        let mut scanned = 0;
+        let lines = "a\nb\nc".split_terminator('\n').inspect(|_| scanned += 1);
+        assert!(!any_line_matches(lines, &reg));
+        assert_eq!(scanned, 3);
+    }
+
+    #[test]
+    fn highlighting_line_mapped_test() {
+        for line in ["", "@a", "żółw @b @c", "@a@b", "x @ y"] {
+            let (highlighted_line, ranges) =
+                highlight_line_mapped(line.to_string(), r"@\w+|\b", STYLE);
+
+            assert_eq!(
+                highlighted_line,
+                crate::highlight_line(line.to_string(), r"@\w+|\b", STYLE)
+            );
+            assert_eq!(highlighted_line.spans.len(), ranges.len());
+            for (span, range) in highlighted_line.spans.iter().zip(&ranges) {
+                assert_eq!(span.content, &line[range.clone()]);
+            }
+            assert!(ranges.windows(2).all(|pair| pair[0].end == pair[1].start));
+            assert_eq!(ranges.first().map_or(0, |range| range.start), 0);
+            assert_eq!(ranges.last().map_or(0, |range| range.end), line.len());
+        }
+    }
+}
@@ -0,0 +1,968 @@
+use std::{net::IpAddr, ops::Range, str::FromStr, sync::LazyLock};
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+use regex::Regex;
+
+use crate::{
+    highlight_text_with,
+    segments::{fill_gaps, styled_line},
+};
+
+/// Matches file paths, optionally followed by `:line` or `:line:column`.
+///
+/// A path is a run of `[\w.-]` segments joined by `/` or `\`, optionally starting with a
+/// Windows drive letter, `~`, `.` or `..`, with a last segment ending in a word character (so a
+/// trailing `.` ending a sentence isn't included). A bare file name without any separator, such
+/// as `lib.rs`, is only recognized when followed by a line number, because on its own it's too
+/// easily confused with regular words (think `e.g.`).
+///
+/// This is a heuristic, not a parser. Known limitations:
+///
+/// * the path part of a URL (`https://example.com/a`) is matched as `/example.com/a`,
+/// * anything with a separator is a path, including dates like `2024/01/02` or fractions,
+/// * paths containing spaces or other characters outside `[\w.~-]` are cut short,
+/// * a trailing `:number` is always read as a line number, even when it's e.g. a port.
+pub const PATH_PATTERN: &str = r"(?<path>(?:[A-Za-z]:)?[\w.~-]*(?:[/\\][\w.-]*\w)+)(?::(?<row>\d+)(?::(?<column>\d+))?)?|(?<file>[\w-][\w.-]*\.\w+):(?<file_row>\d+)(?::(?<file_column>\d+))?";
+
+static PATH_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(PATH_PATTERN).unwrap());
+
+/// A file path reference found by [`find_paths`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathRef {
+    /// The zero-based index of the line containing the reference.
+    pub line: usize,
+    /// The range of bytes covered by the whole reference, relative to the start of its line.
+    pub byte_range: Range<usize>,
+    /// The path itself, without the line and column.
+    pub path: String,
+    /// The line number following the path, if any.
+    pub row: Option<usize>,
+    /// The column number following the line number, if any.
+    pub column: Option<usize>,
+}
+
+/// Creates `Text` from the given `text` argument and adds `highlight_style` to file paths and
+/// `path:line:column` references, as matched by [`PATH_PATTERN`].
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_paths;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = String::from("error at src/lib.rs:42:10");
+/// let highlight_style = Style::new().fg(Color::Cyan);
+///
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("error at "),
+///     Span::from("src/lib.rs:42:10").style(Style::new().fg(Color::Cyan)),
+/// ])]);
+///
+/// assert_eq!(highlight_paths(text, highlight_style), expected_text);
+/// ```
+pub fn highlight_paths<'a>(text: String, highlight_style: Style) -> Text<'a> {
+    highlight_text_with(&text, &PATH_REGEX, highlight_style)
+}
+
+/// Finds every file path reference in `text`, in the same way as [`highlight_paths`], and
+/// splits each into its path, line and column.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{find_paths, PathRef};
+///
+/// assert_eq!(
+///     find_paths("ok\n  --> src/main.rs:3:7"),
+///     vec![PathRef {
+///         line: 1,
+///         byte_range: 6..21,
+///         path: String::from("src/main.rs"),
+///         row: Some(3),
+///         column: Some(7),
+///     }]
+/// );
+/// ```
+pub fn find_paths(text: &str) -> Vec<PathRef> {
+    let number = |m: Option<regex::Match>| m.and_then(|m| m.as_str().parse().ok());
+
+    text.split_terminator('\n')
+        .enumerate()
+        .flat_map(|(line, content)| {
+            PATH_REGEX.captures_iter(content).map(move |caps| {
+                let whole = caps.get(0).unwrap();
+                let (path, row, column) = match caps.name("path") {
+                    Some(path) => (path, caps.name("row"), caps.name("column")),
+                    None => (
+                        caps.name("file").unwrap(),
+                        caps.name("file_row"),
+                        caps.name("file_column"),
+                    ),
+                };
+
+                PathRef {
+                    line,
+                    byte_range: whole.range(),
+                    path: path.as_str().to_string(),
+                    row: number(row),
+                    column: number(column),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Matches `http`, `https`, `ftp` and `file` URLs.
+///
+/// A URL runs until whitespace, a quote or a bracket, except that balanced parentheses are part
+/// of it (as in `https://en.wikipedia.org/wiki/Rust_(programming_language)`), so a URL written
+/// inside parentheses or brackets doesn't swallow the closing one. Trailing punctuation like a
+/// sentence-ending `.` is trimmed by [`highlight_urls`] after matching.
+pub const URL_PATTERN: &str =
+    r#"(?:https?|ftp|file)://(?:[^\s()<>\[\]{}"'`]|\([^\s()<>\[\]{}"'`]*\))+"#;
+
+static URL_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(URL_PATTERN).unwrap());
+
+/// A link found by [`highlight_urls`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UrlLink {
+    /// The zero-based index of the `Line` holding the link.
+    pub line: usize,
+    /// The zero-based index of the `Span` holding the link, within its `Line`.
+    pub span: usize,
+    /// The target of the link.
+    pub url: String,
+}
+
+/// Creates `Text` from the given `text` argument and highlights URLs, as matched by
+/// [`URL_PATTERN`], returning the target of every highlighted `Span` along with it.
+///
+/// URLs are underlined by default, which is the conventional look of a link, and
+/// `highlight_style` is patched on top of that. To get rid of the underline, set it explicitly
+/// with `Style::new().remove_modifier(Modifier::UNDERLINED)`.
+///
+/// Trailing `.`, `,`, `;`, `:`, `!` and `?` are not considered part of a URL, nor is a closing
+/// bracket without a matching opening one inside the URL.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{highlight_urls, UrlLink};
+/// use ratatui::{
+///     style::{Color, Style, Stylize},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = String::from("docs (https://docs.rs/ratatui).");
+/// let (returned_text, links) = highlight_urls(text, Style::new().fg(Color::Blue));
+///
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("docs ("),
+///     Span::from("https://docs.rs/ratatui").style(Style::new().fg(Color::Blue).underlined()),
+///     Span::from(")."),
+/// ])]);
+///
+/// assert_eq!(returned_text, expected_text);
+/// assert_eq!(
+///     links,
+///     vec![UrlLink { line: 0, span: 1, url: String::from("https://docs.rs/ratatui") }]
+/// );
+/// ```
+pub fn highlight_urls<'a>(text: String, highlight_style: Style) -> (Text<'a>, Vec<UrlLink>) {
+    let style = Style::new()
+        .add_modifier(Modifier::UNDERLINED)
+        .patch(highlight_style);
+
+    let mut highlighted_text = Text::default();
+    let mut links = Vec::new();
+
+    for (line_index, line) in text.split_terminator('\n').enumerate() {
+        let mut highlighted_line = Line::default();
+        let urls = URL_REGEX.find_iter(line).filter_map(|m| {
+            let url = m.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?']);
+            (!url.ends_with("://")).then_some(m.start()..m.start() + url.len())
+        });
+
+        for (range, url) in fill_gaps(line.len(), urls) {
+            let span = Span::from(line[range].to_string());
+            if url.is_some() {
+                links.push(UrlLink {
+                    line: line_index,
+                    span: highlighted_line.spans.len(),
+                    url: span.content.to_string(),
+                });
+                highlighted_line.push_span(span.style(style));
+            } else {
+                highlighted_line.push_span(span);
+            }
+        }
+
+        highlighted_text.push_line(highlighted_line);
+    }
+
+    (highlighted_text, links)
+}
+
+/// Matches IPv4 and IPv6 address candidates.
+///
+/// IPv4 addresses are four dot-separated groups of up to three digits. IPv6 addresses are
+/// colon-separated groups of up to four hex digits, with `::` compression and an optional
+/// embedded IPv4 address (`::ffff:192.0.2.1`). Zone indices (`fe80::1%eth0`) and CIDR suffixes
+/// aren't part of the match.
+///
+/// The pattern is loose on purpose, and things like `999.1.1.1` or a time like `12:30:45`
+/// match it too: [`highlight_ips`] and [`highlight_ips_by`] only keep the candidates that
+/// parse as an `IpAddr` and that aren't glued to surrounding words, dots or colons.
+pub const IP_PATTERN: &str = r"(?:[0-9A-Fa-f]{0,4}:){2,7}(?:(?:\d{1,3}\.){3}\d{1,3}|[0-9A-Fa-f]{0,4})|(?:\d{1,3}\.){3}\d{1,3}";
+
+static IP_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(IP_PATTERN).unwrap());
+
+/// Creates `Text` from the given `text` argument and adds `highlight_style` to every IPv4 and
+/// IPv6 address, see [`IP_PATTERN`].
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_ips;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = String::from("from 192.0.2.1 via fe80::1");
+/// let highlight_style = Style::new().fg(Color::Cyan);
+///
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("from "),
+///     Span::from("192.0.2.1").style(Style::new().fg(Color::Cyan)),
+///     Span::from(" via "),
+///     Span::from("fe80::1").style(Style::new().fg(Color::Cyan)),
+/// ])]);
+///
+/// assert_eq!(highlight_ips(text, highlight_style), expected_text);
+/// ```
+pub fn highlight_ips<'a>(text: String, highlight_style: Style) -> Text<'a> {
+    highlight_ips_by(text, |_| highlight_style)
+}
+
+/// Creates `Text` like [`highlight_ips`], but styles every address with the style `f`
+/// computes from it, e.g. to dim private addresses.
+///
+/// # Example
+///
+/// ```
+/// use std::net::IpAddr;
+///
+/// use tui_pattern_highlighter::highlight_ips_by;
+/// use ratatui::{
+///     style::{Color, Style, Stylize},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = String::from("10.0.0.1 -> 8.8.8.8");
+/// let style = |ip: IpAddr| match ip {
+///     IpAddr::V4(ip) if ip.is_private() => Style::new().dim(),
+///     _ => Style::new().fg(Color::Cyan),
+/// };
+///
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("10.0.0.1").style(Style::new().dim()),
+///     Span::from(" -> "),
+///     Span::from("8.8.8.8").style(Style::new().fg(Color::Cyan)),
+/// ])]);
+///
+/// assert_eq!(highlight_ips_by(text, style), expected_text);
+/// ```
+pub fn highlight_ips_by<'a, F: FnMut(IpAddr) -> Style>(text: String, mut f: F) -> Text<'a> {
+    text.split_terminator('\n')
+        .map(|line| {
+            let ips = IP_REGEX.find_iter(line).filter_map(|m| {
+                // the IPv6 part of the pattern swallows a colon ending a sentence or a label
+                let ends = if m.as_str().ends_with(':') {
+                    &[m.end(), m.end() - 1][..]
+                } else {
+                    &[m.end()]
+                };
+                let (ip, end) = ends.iter().find_map(|&end| {
+                    let ip = line[m.start()..end].parse::<IpAddr>().ok()?;
+                    is_standalone_ip(line, m.start()..end).then_some((ip, end))
+                })?;
+
+                Some((m.start()..end, f(ip)))
+            });
+
+            styled_line(line, ips)
+        })
+        .collect()
+}
+
+/// Checks that the address candidate at `range` isn't part of a larger token, like a version
+/// number (`1.2.3.4.5`) or a hex string. A dot or a colon right after the candidate is fine
+/// when it ends a sentence or a label, that is when it isn't followed by an alphanumeric char.
+fn is_standalone_ip(line: &str, range: Range<usize>) -> bool {
+    let is_glued = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut before = line[..range.start].chars().rev();
+    let mut after = line[range.end..].chars();
+
+    let before_ok = match before.next() {
+        Some('.' | ':') => !before.next().is_some_and(is_glued),
+        Some(c) => !is_glued(c),
+        None => true,
+    };
+    let after_ok = match after.next() {
+        Some('.' | ':') => !after.next().is_some_and(is_glued),
+        Some(c) => !is_glued(c),
+        None => true,
+    };
+
+    before_ok && after_ok
+}
+
+/// Matches phone numbers written with separators, in common international and US formats.
+///
+/// A phone number is an optional `+` country code, an optional area code in parentheses, and
+/// two to four groups of digits separated by a space, `.` or `-`, the last group having three
+/// or four digits. This covers formats like:
+///
+/// * `+1 (555) 123-4567`, `(555) 123-4567`, `555-123-4567`, `555.123.4567`, `123-4567`,
+/// * `+44 20 7946 0958`, `+1-555-123-4567`, `+49 30 1234 5678`.
+///
+/// Numbers without any separator, like `5551234567`, aren't matched, since they can't be told
+/// apart from other long numbers, and neither are formats ending in a group of two digits,
+/// like the French `01 23 45 67 89`, or of more than four, like `+49 30 123456`.
+///
+/// On top of the pattern, [`highlight_phones`] only keeps numbers with 7 to 15 digits that
+/// aren't glued to a surrounding word, or to more digits through a `.` or `-`. This rules out
+/// things like 16-digit card numbers written in groups of four, or parts of longer numbers.
+pub const PHONE_PATTERN: &str =
+    r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{1,4}\)[ .-]?)?\d{1,4}(?:[ .-]\d{2,4}){0,2}[ .-]\d{3,4}\b";
+
+static PHONE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(PHONE_PATTERN).unwrap());
+
+/// Creates `Text` from the given `text` argument and adds `highlight_style` to every phone
+/// number, see [`PHONE_PATTERN`].
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_phones;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = String::from("call +1 (555) 123-4567 now");
+/// let highlight_style = Style::new().fg(Color::Green);
+///
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("call "),
+///     Span::from("+1 (555) 123-4567").style(Style::new().fg(Color::Green)),
+///     Span::from(" now"),
+/// ])]);
+///
+/// assert_eq!(highlight_phones(text, highlight_style), expected_text);
+/// ```
+pub fn highlight_phones<'a>(text: String, highlight_style: Style) -> Text<'a> {
+    text.split_terminator('\n')
+        .map(|line| {
+            let phones = PHONE_REGEX
+                .find_iter(line)
+                .filter(|m| {
+                    let digits = m.as_str().chars().filter(char::is_ascii_digit).count();
+                    (7..=15).contains(&digits) && is_standalone_phone(line, m.range())
+                })
+                .map(|m| (m.range(), highlight_style));
+
+            styled_line(line, phones)
+        })
+        .collect()
+}
+
+/// Checks that the phone number candidate at `range` isn't part of a longer run of digits and
+/// separators, like a card number or a version.
+fn is_standalone_phone(line: &str, range: Range<usize>) -> bool {
+    let is_glued = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut before = line[..range.start].chars().rev();
+    let mut after = line[range.end..].chars();
+
+    let before_ok = match before.next() {
+        Some('+' | '(') => false,
+        Some('.' | '-') => !before.next().is_some_and(|c| c.is_ascii_digit()),
+        Some(c) => !is_glued(c),
+        None => true,
+    };
+    let after_ok = match after.next() {
+        Some('.' | '-') => !after.next().is_some_and(|c| c.is_ascii_digit()),
+        Some(c) => !is_glued(c),
+        None => true,
+    };
+
+    before_ok && after_ok
+}
+
+/// An attention-grabbing style for critical alerts: reverse video and bold.
+///
+/// Both modifiers are supported by practically every terminal, so this renders the same
+/// everywhere.
+pub const ALERT_STYLE: Style = Style::new().add_modifier(Modifier::REVERSED.union(Modifier::BOLD));
+
+/// [`ALERT_STYLE`] with rapid blinking on top.
+///
+/// Many terminals don't support `RAPID_BLINK`, and either blink slowly or ignore it. They still
+/// render the reverse video and bold, so on those terminals this degrades to [`ALERT_STYLE`].
+pub const BLINKING_ALERT_STYLE: Style = ALERT_STYLE.add_modifier(Modifier::RAPID_BLINK);
+
+/// Creates `Text` from the given `text` argument and styles the matches of `pattern` as
+/// critical alerts, with [`BLINKING_ALERT_STYLE`] if `blink` is set and with [`ALERT_STYLE`]
+/// otherwise.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{highlight_alert, ALERT_STYLE};
+/// use ratatui::text::{Line, Span, Text};
+///
+/// let text = String::from("db: CRITICAL");
+///
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("db: "),
+///     Span::from("CRITICAL").style(ALERT_STYLE),
+/// ])]);
+///
+/// assert_eq!(highlight_alert(text, "CRITICAL", false), expected_text);
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+pub fn highlight_alert<'a>(text: String, pattern: impl AsRef<str>, blink: bool) -> Text<'a> {
+    let reg = Regex::new(pattern.as_ref()).unwrap();
+    let style = if blink {
+        BLINKING_ALERT_STYLE
+    } else {
+        ALERT_STYLE
+    };
+
+    highlight_text_with(&text, &reg, style)
+}
+
+static COLOR_NAME_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"#[0-9A-Fa-f]{6}\b|\b[A-Za-z]+(?:[_-][A-Za-z]+)*\b").unwrap());
+
+/// Creates `Text` from the given `text` argument and renders every color name or hex code in
+/// it in the color it names, as its foreground color if `match_fg` is set and as its
+/// background color otherwise. Made for live previews in config and theme editors.
+///
+/// Words are parsed with `Color::from_str`, so everything ratatui understands is recognized:
+///
+/// * the 16 ANSI names, like `red`, `dark_gray` or `light_blue`, in any case and with words
+///   optionally separated by `_` or `-` (`LightBlue`, `light-blue`, `light_blue`),
+/// * the aliases `bright` for `light`, `grey` and `silver` for `gray`, so e.g. `bright_blue`
+///   and `light_blue` are the same color,
+/// * hex codes in the `#rrggbb` form.
+///
+/// Unknown words stay plain, and so do `reset`, which isn't a color, and color indices like
+/// `42`, which are too easily confused with other numbers.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_color_names;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = String::from("fg = bright_blue, bg = #ff8000");
+///
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("fg = "),
+///     Span::from("bright_blue").style(Style::new().fg(Color::LightBlue)),
+///     Span::from(", bg = "),
+///     Span::from("#ff8000").style(Style::new().fg(Color::Rgb(255, 128, 0))),
+/// ])]);
+///
+/// assert_eq!(highlight_color_names(text, true), expected_text);
+/// ```
+pub fn highlight_color_names<'a>(text: String, match_fg: bool) -> Text<'a> {
+    text.split_terminator('\n')
+        .map(|line| {
+            let colors = COLOR_NAME_REGEX.find_iter(line).filter_map(|m| {
+                let color = match Color::from_str(m.as_str()) {
+                    Ok(Color::Reset) | Err(_) => return None,
+                    Ok(color) => color,
+                };
+                let style = if match_fg {
+                    Style::new().fg(color)
+                } else {
+                    Style::new().bg(color)
+                };
+
+                Some((m.range(), style))
+            });
+
+            styled_line(line, colors)
+        })
+        .collect()
+}
+
+/// The styles used by [`highlight_code`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CodeTheme {
+    /// The style of integer and float literals.
+    pub number: Style,
+    /// The style of double and single quoted strings.
+    pub string: Style,
+    /// The style of `//` and `#` line comments.
+    pub comment: Style,
+}
+
+static CODE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?<comment>(?://|#).*)|(?<string>"(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*')|(?<number>\b(?:0[xX][0-9a-fA-F_]+|0[bB][01_]+|\d[\d_]*(?:\.\d[\d_]*)?(?:[eE][+-]?\d+)?)\b)"#,
+    )
+    .unwrap()
+});
+
+/// Creates `Text` from the given `text` argument and styles the tokens common to most
+/// programming languages with the styles of `theme`: number literals, strings and line
+/// comments.
+///
+/// Tokens are recognized by regular expressions rather than a parser, so this is a good-enough
+/// way to make code-ish text readable, not a syntax highlighter:
+///
+/// * numbers are decimal integers and floats with an optional exponent, and `0x`/`0b` prefixed
+///   integers, with `_` separators allowed,
+/// * strings are delimited by `"` or `'` on a single line, and escaped quotes (`\"`) don't end
+///   them; a quote without a closing one is not a string, but an apostrophe followed by another
+///   one later on the line is, even in prose,
+/// * comments start at `//` or `#` and run until the end of the line, so block comments and
+///   languages with other comment markers aren't supported, and a `#` used for anything else
+///   (like `#[derive]`) starts a comment too.
+///
+/// Whatever starts first wins, so a number or a `#` inside a string, or a string inside a
+/// comment, is part of the enclosing token and isn't styled separately.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{highlight_code, CodeTheme};
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let theme = CodeTheme {
+///     number: Style::new().fg(Color::Magenta),
+///     string: Style::new().fg(Color::Green),
+///     comment: Style::new().fg(Color::DarkGray),
+/// };
+///
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("x = "),
+///     Span::from("42").style(Style::new().fg(Color::Magenta)),
+///     Span::from(" + f("),
+///     Span::from("\"v2\"").style(Style::new().fg(Color::Green)),
+///     Span::from(") "),
+///     Span::from("// 7").style(Style::new().fg(Color::DarkGray)),
+/// ])]);
+///
+/// assert_eq!(highlight_code(String::from(r#"x = 42 + f("v2") // 7"#), theme), expected_text);
+/// ```
+pub fn highlight_code<'a>(text: String, theme: CodeTheme) -> Text<'a> {
+    text.split_terminator('\n')
+        .map(|line| {
+            let tokens = CODE_REGEX.captures_iter(line).map(|caps| {
+                let style = if caps.name("comment").is_some() {
+                    theme.comment
+                } else if caps.name("string").is_some() {
+                    theme.string
+                } else {
+                    theme.number
+                };
+
+                (caps.get(0).unwrap().range(), style)
+            });
+
+            styled_line(line, tokens)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{
+        style::Color,
+        text::{Line, Span},
+    };
+
+    const STYLE: Style = Style::new().fg(Color::Cyan);
+
+    fn matched(text: &str) -> Vec<&str> {
+        PATH_REGEX.find_iter(text).map(|m| m.as_str()).collect()
+    }
+
+    #[test]
+    fn path_pattern_test() {
+        assert_eq!(matched("see src/lib.rs."), vec!["src/lib.rs"]);
+        assert_eq!(matched("./run.sh and ../x/y"), vec!["./run.sh", "../x/y"]);
+        assert_eq!(
+            matched("/usr/bin/env ~/.config"),
+            vec!["/usr/bin/env", "~/.config"]
+        );
+        assert_eq!(
+            matched(r"C:\Users\me\a.rs:1:2"),
+            vec![r"C:\Users\me\a.rs:1:2"]
+        );
+        assert_eq!(
+            matched("lib.rs:10 but not lib.rs, e.g. this"),
+            vec!["lib.rs:10"]
+        );
+        assert_eq!(matched("no paths: here"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn highlighting_paths_test() {
+        assert_eq!(
+            highlight_paths(String::from("at tests/a.rs:5: oops"), STYLE),
+            Text::from(vec![Line::from(vec![
+                Span::from("at "),
+                Span::from("tests/a.rs:5").style(STYLE),
+                Span::from(": oops"),
+            ])])
+        );
+    }
+
+    #[test]
+    fn finding_paths_test() {
+        assert_eq!(
+            find_paths("src/a.rs and main.rs:12\nb/c.rs:1:9"),
+            vec![
+                PathRef {
+                    line: 0,
+                    byte_range: 0..8,
+                    path: String::from("src/a.rs"),
+                    row: None,
+                    column: None,
+                },
+                PathRef {
+                    line: 0,
+                    byte_range: 13..23,
+                    path: String::from("main.rs"),
+                    row: Some(12),
+                    column: None,
+                },
+                PathRef {
+                    line: 1,
+                    byte_range: 0..10,
+                    path: String::from("b/c.rs"),
+                    row: Some(1),
+                    column: Some(9),
+                },
+            ]
+        );
+    }
+
+    fn urls(text: &str) -> Vec<String> {
+        highlight_urls(text.to_string(), STYLE)
+            .1
+            .into_iter()
+            .map(|link| link.url)
+            .collect()
+    }
+
+    #[test]
+    fn url_pattern_test() {
+        let cases = [
+            ("http://a", vec!["http://a"]),
+            ("go to https://example.com.", vec!["https://example.com"]),
+            (
+                "https://example.com/path?q=1&r=two#frag, next",
+                vec!["https://example.com/path?q=1&r=two#frag"],
+            ),
+            ("(see https://docs.rs/regex)", vec!["https://docs.rs/regex"]),
+            (
+                "[link](https://github.com/a/b)",
+                vec!["https://github.com/a/b"],
+            ),
+            ("<https://example.org/x>", vec!["https://example.org/x"]),
+            ("\"https://example.org/q\"", vec!["https://example.org/q"]),
+            (
+                "https://en.wikipedia.org/wiki/Rust_(programming_language)!",
+                vec!["https://en.wikipedia.org/wiki/Rust_(programming_language)"],
+            ),
+            (
+                "http://localhost:8080/api and ftp://ftp.example.com/file.tar.gz",
+                vec![
+                    "http://localhost:8080/api",
+                    "ftp://ftp.example.com/file.tar.gz",
+                ],
+            ),
+            (
+                "file:///home/me/notes.txt?",
+                vec!["file:///home/me/notes.txt"],
+            ),
+            (
+                "https://user:pw@host.io/a;b",
+                vec!["https://user:pw@host.io/a;b"],
+            ),
+            ("http://. and no scheme example.com", vec![]),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(urls(text), expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn highlighting_urls_test() {
+        let underlined = STYLE.add_modifier(Modifier::UNDERLINED);
+        let (returned_text, links) = highlight_urls(
+            String::from("a http://a.b\n\nhttp://c.d, http://e.f"),
+            STYLE,
+        );
+
+        assert_eq!(
+            returned_text,
+            Text::from(vec![
+                Line::from(vec![
+                    Span::from("a "),
+                    Span::from("http://a.b").style(underlined),
+                ]),
+                Line::default(),
+                Line::from(vec![
+                    Span::from("http://c.d").style(underlined),
+                    Span::from(", "),
+                    Span::from("http://e.f").style(underlined),
+                ]),
+            ])
+        );
+        assert_eq!(
+            links,
+            vec![
+                UrlLink {
+                    line: 0,
+                    span: 1,
+                    url: String::from("http://a.b"),
+                },
+                UrlLink {
+                    line: 2,
+                    span: 0,
+                    url: String::from("http://c.d"),
+                },
+                UrlLink {
+                    line: 2,
+                    span: 2,
+                    url: String::from("http://e.f"),
+                },
+            ]
+        );
+
+        let (returned_text, _) = highlight_urls(
+            String::from("http://a.b"),
+            Style::new().remove_modifier(Modifier::UNDERLINED),
+        );
+        assert_eq!(
+            returned_text.lines[0].spans[0].style,
+            Style::new().remove_modifier(Modifier::UNDERLINED)
+        );
+    }
+
+    #[test]
+    fn highlighting_code_test() {
+        let theme = CodeTheme {
+            number: Style::new().fg(Color::Magenta),
+            string: Style::new().fg(Color::Green),
+            comment: Style::new().fg(Color::DarkGray),
+        };
+        let tokens = |line: &str| {
+            highlight_code(line.to_string(), theme).lines[0]
+                .spans
+                .iter()
+                .filter(|span| span.style != Style::new())
+                .map(|span| (span.content.to_string(), span.style))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            tokens(r#"let s = "a \"1\" # b"; // 2 'c'"#),
+            vec![
+                (String::from(r#""a \"1\" # b""#), theme.string),
+                (String::from("// 2 'c'"), theme.comment),
+            ]
+        );
+        assert_eq!(
+            tokens("n = 0xFF + 0b1010 + 1_000 + 3.14e-2 + x1 + 2"),
+            vec![
+                (String::from("0xFF"), theme.number),
+                (String::from("0b1010"), theme.number),
+                (String::from("1_000"), theme.number),
+                (String::from("3.14e-2"), theme.number),
+                (String::from("2"), theme.number),
+            ]
+        );
+        assert_eq!(
+            tokens("x = 'it\\'s' # done"),
+            vec![
+                (String::from("'it\\'s'"), theme.string),
+                (String::from("# done"), theme.comment),
+            ]
+        );
+        assert_eq!(
+            tokens(r#"unterminated "string 5"#),
+            vec![(String::from("5"), theme.number)]
+        );
+    }
+
+    #[test]
+    fn highlighting_alert_test() {
+        let alert_modifiers = Modifier::REVERSED | Modifier::BOLD;
+        assert_eq!(ALERT_STYLE.add_modifier, alert_modifiers);
+        assert_eq!(
+            BLINKING_ALERT_STYLE.add_modifier,
+            alert_modifiers | Modifier::RAPID_BLINK
+        );
+
+        let text = String::from("ok\nFATAL: down, FATAL");
+        assert_eq!(
+            highlight_alert(text.clone(), "FATAL", true),
+            Text::from(vec![
+                Line::from(vec![Span::from("ok")]),
+                Line::from(vec![
+                    Span::from("FATAL").style(BLINKING_ALERT_STYLE),
+                    Span::from(": down, "),
+                    Span::from("FATAL").style(BLINKING_ALERT_STYLE),
+                ]),
+            ])
+        );
+        assert_eq!(
+            highlight_alert(text.clone(), "FATAL", false),
+            crate::highlight_text(text, "FATAL", ALERT_STYLE)
+        );
+    }
+
+    #[test]
+    fn highlighting_color_names_test() {
+        let text = String::from("Red grey Light-Cyan reset 42 #00ff0 #0000FF\nredish #abcdefg");
+
+        assert_eq!(
+            highlight_color_names(text, false),
+            Text::from(vec![
+                Line::from(vec![
+                    Span::from("Red").style(Style::new().bg(Color::Red)),
+                    Span::from(" "),
+                    Span::from("grey").style(Style::new().bg(Color::Gray)),
+                    Span::from(" "),
+                    Span::from("Light-Cyan").style(Style::new().bg(Color::LightCyan)),
+                    Span::from(" reset 42 #00ff0 "),
+                    Span::from("#0000FF").style(Style::new().bg(Color::Rgb(0, 0, 255))),
+                ]),
+                Line::from(vec![Span::from("redish #abcdefg")]),
+            ])
+        );
+    }
+
+    #[test]
+    fn highlighting_ips_test() {
+        let ips = |line: &str| {
+            highlight_ips(line.to_string(), STYLE).lines[0]
+                .spans
+                .iter()
+                .filter(|span| span.style == STYLE)
+                .map(|span| span.content.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ips("127.0.0.1, 255.255.255.255. [10.1.2.3]:80 1.2.3.4.5 a1.2.3.4 999.1.1.1"),
+            vec!["127.0.0.1", "255.255.255.255", "10.1.2.3"]
+        );
+        assert_eq!(
+            ips("::1 :: 2001:db8::ff00:42:8329 ::ffff:192.0.2.1 fe80::1%eth0 (1::)"),
+            vec![
+                "::1",
+                "::",
+                "2001:db8::ff00:42:8329",
+                "::ffff:192.0.2.1",
+                "fe80::1",
+                "1::"
+            ]
+        );
+        assert_eq!(
+            ips("12:30:45 1:2:3:4:5:6:7:8:9 2001:db8:::1 deadbeef::1 at 1::2:"),
+            vec!["1::2"]
+        );
+    }
+
+    #[test]
+    fn highlighting_ips_by_test() {
+        let mut seen = Vec::new();
+
+        let text = highlight_ips_by(String::from("a 10.0.0.1\n::1 b"), |ip| {
+            seen.push(ip);
+            if ip.is_loopback() {
+                STYLE
+            } else {
+                Style::new()
+            }
+        });
+
+        assert_eq!(
+            text,
+            Text::from(vec![
+                Line::from(vec![Span::from("a "), Span::from("10.0.0.1")]),
+                Line::from(vec![Span::from("::1").style(STYLE), Span::from(" b")]),
+            ])
+        );
+        assert_eq!(
+            seen,
+            vec![
+                "10.0.0.1".parse::<IpAddr>().unwrap(),
+                "::1".parse::<IpAddr>().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn highlighting_phones_test() {
+        let phones = |line: &str| {
+            highlight_phones(line.to_string(), STYLE).lines[0]
+                .spans
+                .iter()
+                .filter(|span| span.style == STYLE)
+                .map(|span| span.content.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            phones("+1 (555) 123-4567, (555) 123-4567 or 555.123.4567; 123-4567."),
+            vec![
+                "+1 (555) 123-4567",
+                "(555) 123-4567",
+                "555.123.4567",
+                "123-4567"
+            ]
+        );
+        assert_eq!(
+            phones("+44 20 7946 0958 +49 30 1234 5678 +1-555-123-4567 1 555 123 4567"),
+            vec![
+                "+44 20 7946 0958",
+                "+49 30 1234 5678",
+                "+1-555-123-4567",
+                "1 555 123 4567"
+            ]
+        );
+        assert!(
+            phones("4111 1111 1111 1111 5551234567 2024-01-01 12-3456 v555-123-4567").is_empty()
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+/// Whether a [`QuoteFilter`] keeps the matches inside quoted regions or outside of them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuoteMode {
+    /// Only highlights matches inside quoted regions.
+    Inside,
+    /// Only highlights matches outside of quoted regions.
+    #[default]
+    Outside,
+}
+
+/// Restricts highlighting to the inside or the outside of quoted regions, see
+/// [`Highlighter::quotes`](crate::Highlighter::quotes).
+///
+/// Every line is scanned from left to right for quoted regions, with these rules:
+///
+/// * a region starts at any of the `quotes` chars and ends at the next occurrence of the same
+///   char, so `"it's"` is one region and the `'` in it is just a char,
+/// * a region includes its opening and closing quotes,
+/// * the `escape` char makes the char right after it ordinary, inside as well as outside of
+///   regions, so `\"` neither opens nor closes a region,
+/// * a region without a closing quote runs until the end of the line, and regions never span
+///   several lines.
+///
+/// A match belongs inside when it's entirely within a region, and outside when it doesn't
+/// touch any region. Matches straddling the edge of a region are neither, and are never
+/// highlighted. This is a lexer for the common case, not a parser: quoting conventions that
+/// double quotes to escape them (`'it''s'`, as in SQL) or use different opening and closing
+/// chars aren't supported.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QuoteFilter {
+    /// The chars delimiting quoted regions.
+    pub quotes: Vec<char>,
+    /// The char escaping the char after it, if any.
+    pub escape: Option<char>,
+    /// Whether to keep the matches inside or outside of quoted regions.
+    pub mode: QuoteMode,
+}
+
+impl Default for QuoteFilter {
+    fn default() -> Self {
+        Self {
+            quotes: vec!['"', '\''],
+            escape: Some('\\'),
+            mode: QuoteMode::default(),
+        }
+    }
+}
+
+impl QuoteFilter {
+    /// Checks whether the match at `range` should be highlighted, given the quoted `regions`
+    /// of its line as returned by [`regions`](Self::regions).
+    pub(crate) fn keeps(&self, regions: &[Range<usize>], range: &Range<usize>) -> bool {
+        match self.mode {
+            QuoteMode::Inside => regions
+                .iter()
+                .any(|region| region.start <= range.start && range.end <= region.end),
+            QuoteMode::Outside => !regions
+                .iter()
+                .any(|region| region.start < range.end && range.start < region.end),
+        }
+    }
+
+    /// Finds the byte ranges of the quoted regions of `line`.
+    pub(crate) fn regions(&self, line: &str) -> Vec<Range<usize>> {
+        let mut regions = Vec::new();
+        let mut open: Option<(usize, char)> = None;
+        let mut chars = line.char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            if Some(c) == self.escape {
+                chars.next();
+            } else if let Some((start, quote)) = open {
+                if c == quote {
+                    regions.push(start..i + c.len_utf8());
+                    open = None;
+                }
+            } else if self.quotes.contains(&c) {
+                open = Some((i, c));
+            }
+        }
+
+        if let Some((start, _)) = open {
+            regions.push(start..line.len());
+        }
+
+        regions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_regions_test() {
+        let filter = QuoteFilter::default();
+
+        assert_eq!(
+            filter.regions(r#"a "b \" 'c'" 'it"s' \"d "e"#),
+            vec![2..12, 13..19, 24..26]
+        );
+        assert!(filter.regions("no quotes").is_empty());
+        assert!(filter.regions(r#"end\"#).is_empty());
+
+        let filter = QuoteFilter {
+            quotes: vec!['`'],
+            escape: None,
+            ..QuoteFilter::default()
+        };
+        assert_eq!(filter.regions(r#"`a\` "b" `ż"#), vec![0..4, 9..12]);
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    io::{self, BufRead},
+    iter,
+};
+
+use ratatui::{style::Style, text::Line};
+use regex::Regex;
+
+use crate::highlight_line_with;
+
+/// Reads lines from `reader` and highlights them like [`highlight_text`](crate::highlight_text)
+/// does, yielding them in batches of up to `batch` lines.
+///
+/// This is for tailing chatty sources: a UI can append a whole batch per frame instead of
+/// handling lines one by one. Lines are split at `'\n'` only, which is removed, so a `'\r'`
+/// before it is kept like in [`highlight_text`](crate::highlight_text). The last batch holds
+/// whatever is left and may be smaller, and a last line without a line ending is yielded too.
+/// A `batch` of zero is treated as one.
+///
+/// When reading fails, the lines read so far are yielded first, then the error, and then the
+/// iterator ends. Invalid UTF-8 is reported as an error of kind `InvalidData`.
+///
+/// # Arguments
+///
+/// * `reader` - The source of the lines to be highlighted.
+/// * `reg` - A compiled regular expression to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+/// * `batch` - The maximum number of lines per batch.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::highlight_reader_chunked;
+/// use ratatui::style::{Color, Style};
+/// use regex::Regex;
+///
+/// let reader = "a @b\nc\nd @e".as_bytes();
+/// let reg = Regex::new(r"@\w+").unwrap();
+///
+/// let batches = highlight_reader_chunked(reader, &reg, Style::new().bg(Color::Blue), 2)
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 1]);
+/// ```
+pub fn highlight_reader_chunked<'r, R: BufRead + 'r>(
+    mut reader: R,
+    reg: &'r Regex,
+    highlight_style: Style,
+    batch: usize,
+) -> impl Iterator<Item = io::Result<Vec<Line<'static>>>> + 'r {
+    let batch = batch.max(1);
+    let mut buf = String::new();
+    let mut pending_error = None;
+    let mut done = false;
+
+    iter::from_fn(move || {
+        if let Some(err) = pending_error.take() {
+            return Some(Err(err));
+        }
+        if done {
+            return None;
+        }
+
+        let mut lines = Vec::with_capacity(batch);
+        while lines.len() < batch {
+            buf.clear();
+            match reader.read_line(&mut buf) {
+                Ok(0) => {
+                    done = true;
+                    break;
+                }
+                Ok(_) => {
+                    let line = buf.strip_suffix('\n').unwrap_or(&buf);
+                    lines.push(highlight_line_with(line, reg, highlight_style));
+                }
+                Err(err) => {
+                    done = true;
+                    if lines.is_empty() {
+                        return Some(Err(err));
+                    }
+                    pending_error = Some(err);
+                    break;
+                }
+            }
+        }
+
+        (!lines.is_empty()).then_some(Ok(lines))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::highlight_text;
+    use ratatui::style::Color;
+
+    const STYLE: Style = Style::new().bg(Color::Blue);
+
+    #[test]
+    fn highlight_reader_chunked_test() {
+        let reg = Regex::new(r"@\w+").unwrap();
+
+        for text in ["@a\nb\r\n\n@c d\n@e\n", "@a\nb\n@c", "", "\n"] {
+            for batch in [0, 1, 2, 3, 10] {
+                let batches = highlight_reader_chunked(text.as_bytes(), &reg, STYLE, batch)
+                    .collect::<io::Result<Vec<_>>>()
+                    .unwrap();
+
+                assert!(batches
+                    .iter()
+                    .all(|lines| (1..=batch.max(1)).contains(&lines.len())));
+                assert_eq!(
+                    batches.concat(),
+                    highlight_text(text.to_string(), r"@\w+", STYLE).lines
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn highlight_reader_chunked_error_test() {
+        let reg = Regex::new(r"@\w+").unwrap();
+        let reader = io::BufReader::with_capacity(1, &b"@a\nb\n\xff\nc\n"[..]);
+
+        let mut batches = highlight_reader_chunked(reader, &reg, STYLE, 5);
+        assert_eq!(batches.next().unwrap().unwrap().len(), 2);
+        assert_eq!(
+            batches.next().unwrap().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert!(batches.next().is_none());
+    }
+}
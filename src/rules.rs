@@ -0,0 +1,402 @@
+use std::{error::Error, fmt, iter};
+
+use ratatui::{
+    style::Style,
+    text::{Line, Span, Text},
+};
+use regex::{Match, Regex};
+
+use crate::{segments::styled_line, RegexFlags};
+
+/// A labeled pattern and the style of its matches, one of the rules of a [`MultiHighlighter`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rule {
+    /// The name of the category of text the rule matches, like `"error"` or `"url"`.
+    pub label: String,
+    /// The regular expression pattern matching the text of the category.
+    pub pattern: String,
+    /// The style to be applied to the matching text.
+    pub style: Style,
+    /// The flags the pattern is compiled with, independently of the other rules.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub flags: RegexFlags,
+}
+
+impl Rule {
+    /// Creates a new `Rule` compiled with the default [`RegexFlags`].
+    pub fn new(label: impl Into<String>, pattern: impl Into<String>, style: Style) -> Self {
+        Self {
+            label: label.into(),
+            pattern: pattern.into(),
+            style,
+            flags: RegexFlags::default(),
+        }
+    }
+
+    /// Sets the flags the pattern of the rule is compiled with, e.g. to match one rule
+    /// regardless of case while the others stay case-sensitive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::{MultiHighlighter, RegexFlags, Rule};
+    /// use ratatui::{
+    ///     style::{Color, Style},
+    ///     text::{Line, Span},
+    /// };
+    ///
+    /// let case_insensitive = RegexFlags {
+    ///     case_insensitive: true,
+    ///     ..RegexFlags::default()
+    /// };
+    /// let highlighter = MultiHighlighter::new([
+    ///     Rule::new("url", r"https?://\S+", Style::new().fg(Color::Blue)).flags(case_insensitive),
+    ///     Rule::new("todo", "TODO", Style::new().fg(Color::Yellow)),
+    /// ])
+    /// .unwrap();
+    ///
+    /// let expected_line = Line::from(vec![
+    ///     Span::from("HTTPS://x.org").style(Style::new().fg(Color::Blue)),
+    ///     Span::from(" todo"),
+    /// ]);
+    ///
+    /// assert_eq!(highlighter.highlight_line(String::from("HTTPS://x.org todo")), expected_line);
+    /// ```
+    pub fn flags(self, flags: RegexFlags) -> Self {
+        Self { flags, ..self }
+    }
+}
+
+/// The error returned when the pattern of a [`Rule`] is an invalid regular expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleError {
+    /// The label of the invalid rule.
+    pub label: String,
+    /// The error returned by `regex`.
+    pub source: regex::Error,
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid pattern for rule `{}`: {}",
+            self.label, self.source
+        )
+    }
+}
+
+impl Error for RuleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Highlights the matches of several labeled [`Rules`](Rule) at once, each with its own style.
+///
+/// Matches never overlap: the leftmost match of any rule wins, and when several rules match at
+/// the same position, the one that comes first wins. Like everywhere else, empty matches are
+/// ignored.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{MultiHighlighter, Rule};
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let highlighter = MultiHighlighter::new([
+///     Rule::new("mention", r"@\w+", Style::new().fg(Color::Blue)),
+///     Rule::new("tag", r"#\w+", Style::new().fg(Color::Green)),
+/// ])
+/// .unwrap();
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("@buddy").style(Style::new().fg(Color::Blue)),
+///     Span::from(" "),
+///     Span::from("#rust").style(Style::new().fg(Color::Green)),
+/// ]);
+///
+/// assert_eq!(highlighter.highlight_line(String::from("@buddy #rust")), expected_line);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiHighlighter {
+    rules: Vec<Rule>,
+    regexes: Vec<Regex>,
+    summary: bool,
+}
+
+impl MultiHighlighter {
+    /// Compiles the patterns of `rules` into a new `MultiHighlighter`, each with the flags of
+    /// its rule. The order of the rules is their priority.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first rule whose pattern is an invalid regular expression.
+    pub fn new(rules: impl IntoIterator<Item = Rule>) -> Result<Self, RuleError> {
+        let rules = rules.into_iter().collect::<Vec<_>>();
+        let regexes = rules
+            .iter()
+            .map(|rule| {
+                rule.flags.build(&rule.pattern).map_err(|source| RuleError {
+                    label: rule.label.clone(),
+                    source,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            rules,
+            regexes,
+            summary: false,
+        })
+    }
+
+    /// Appends a summary `Line` to the `Text` returned by
+    /// [`highlight_text`](Self::highlight_text), listing `label: count` for every rule that
+    /// matched, in the order of the rules and styled with their style. No summary is appended
+    /// when nothing matched. It's off by default.
+    ///
+    /// The counts are gathered while highlighting, so the text isn't searched a second time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_pattern_highlighter::{MultiHighlighter, Rule};
+    /// use ratatui::{
+    ///     style::{Color, Style},
+    ///     text::{Line, Span},
+    /// };
+    ///
+    /// let highlighter = MultiHighlighter::new([
+    ///     Rule::new("error", "error", Style::new().fg(Color::Red)),
+    ///     Rule::new("warning", "warning", Style::new().fg(Color::Yellow)),
+    ///     Rule::new("note", "note", Style::new().fg(Color::Blue)),
+    /// ])
+    /// .unwrap()
+    /// .summary(true);
+    ///
+    /// let text = highlighter.highlight_text(String::from("error\nwarning\nerror"));
+    ///
+    /// assert_eq!(
+    ///     text.lines.last(),
+    ///     Some(&Line::from(vec![
+    ///         Span::from("error: 2").style(Style::new().fg(Color::Red)),
+    ///         Span::from(", "),
+    ///         Span::from("warning: 1").style(Style::new().fg(Color::Yellow)),
+    ///     ]))
+    /// );
+    /// ```
+    pub fn summary(self, summary: bool) -> Self {
+        Self { summary, ..self }
+    }
+
+    /// Returns the rules, in the order of their priority.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Creates a `Line` like [`highlight_line`](crate::highlight_line), styling the matches of
+    /// every rule with its style. The summary is never added to a single `Line`.
+    pub fn highlight_line<'a>(&self, line: String) -> Line<'a> {
+        self.line(&line, &mut vec![0; self.rules.len()])
+    }
+
+    /// Creates `Text` like [`highlight_text`](crate::highlight_text), styling the matches of
+    /// every rule with its style, and followed by the summary if it's enabled.
+    pub fn highlight_text<'a>(&self, text: String) -> Text<'a> {
+        let mut counts = vec![0; self.rules.len()];
+        let mut highlighted_text = text
+            .split_terminator('\n')
+            .map(|line| self.line(line, &mut counts))
+            .collect::<Text>();
+
+        if self.summary && counts.iter().any(|&count| count > 0) {
+            highlighted_text.push_line(self.summary_line(&counts));
+        }
+
+        highlighted_text
+    }
+
+    fn line<'a>(&self, line: &str, counts: &mut [usize]) -> Line<'a> {
+        let mut next_matches = self
+            .regexes
+            .iter()
+            .map(|reg| find_non_empty(reg, line, 0))
+            .collect::<Vec<_>>();
+
+        let mut at = 0;
+        let matches = iter::from_fn(|| {
+            let (i, m) = self.find_at(line, at, &mut next_matches)?;
+            counts[i] += 1;
+            at = m.end();
+            Some((m.range(), self.rules[i].style))
+        });
+
+        styled_line(line, matches)
+    }
+
+    /// Finds the leftmost non-empty match of any rule starting at or after `at`, along with the
+    /// index of the rule.
+    ///
+    /// `next_matches` holds the next non-empty match of every rule, like the state of a regex
+    /// iterator, so a rule is only searched again once the search has moved past its next match.
+    fn find_at<'h>(
+        &self,
+        line: &'h str,
+        at: usize,
+        next_matches: &mut [Option<Match<'h>>],
+    ) -> Option<(usize, Match<'h>)> {
+        for (reg, next_match) in self.regexes.iter().zip(next_matches.iter_mut()) {
+            if next_match.is_some_and(|m| m.start() < at) {
+                *next_match = find_non_empty(reg, line, at);
+            }
+        }
+
+        next_matches
+            .iter()
+            .enumerate()
+            .filter_map(|(i, m)| Some((i, (*m)?)))
+            .min_by_key(|(i, m)| (m.start(), *i))
+    }
+
+    fn summary_line<'a>(&self, counts: &[usize]) -> Line<'a> {
+        let mut summary_line = Line::default();
+
+        for (rule, &count) in self
+            .rules
+            .iter()
+            .zip(counts)
+            .filter(|(_, &count)| count > 0)
+        {
+            if !summary_line.spans.is_empty() {
+                summary_line.push_span(Span::from(", "));
+            }
+            summary_line
+                .push_span(Span::from(format!("{}: {count}", rule.label)).style(rule.style));
+        }
+
+        summary_line
+    }
+}
+
+/// Finds the leftmost non-empty match of `reg` starting at or after `at`.
+fn find_non_empty<'h>(reg: &Regex, line: &'h str, mut at: usize) -> Option<Match<'h>> {
+    loop {
+        let m = reg.find_at(line, at)?;
+        if !m.is_empty() {
+            return Some(m);
+        }
+        at = m.start() + line[m.start()..].chars().next()?.len_utf8();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    const STYLE: Style = Style::new().bg(Color::Blue);
+
+    #[test]
+    fn multi_highlighter_test() {
+        let other = Style::new().fg(Color::Red);
+        let highlighter = MultiHighlighter::new([
+            Rule::new("word", r"\b\w{4}\b", STYLE),
+            Rule::new("mention", r"@\w+|\w+|\b", other),
+            Rule::new("empty", r"x*", other),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            highlighter.highlight_text(String::from("@John said @Bill\nwell")),
+            Text::from(vec![
+                Line::from(vec![
+                    Span::from("@John").style(other),
+                    Span::from(" "),
+                    Span::from("said").style(STYLE),
+                    Span::from(" "),
+                    Span::from("@Bill").style(other),
+                ]),
+                Line::from(vec![Span::from("well").style(STYLE)]),
+            ])
+        );
+
+        let err = MultiHighlighter::new([
+            Rule::new("ok", "a", STYLE),
+            Rule::new("bad", "(", STYLE),
+            Rule::new("worse", "[", STYLE),
+        ])
+        .unwrap_err();
+        assert_eq!(err.label, "bad");
+        assert!(err
+            .to_string()
+            .starts_with("invalid pattern for rule `bad`"));
+    }
+
+    #[test]
+    fn multi_highlighter_summary_test() {
+        let other = Style::new().fg(Color::Red);
+        let highlighter = MultiHighlighter::new([
+            Rule::new("mention", r"@\w+", STYLE),
+            Rule::new("tag", r"#\w+", other),
+            Rule::new("number", r"\d+", other),
+        ])
+        .unwrap();
+
+        let text = String::from("@a #b\n#c @d #e 1");
+        let summary_line = Line::from(vec![
+            Span::from("mention: 2").style(STYLE),
+            Span::from(", "),
+            Span::from("tag: 3").style(other),
+            Span::from(", "),
+            Span::from("number: 1").style(other),
+        ]);
+
+        let without_summary = highlighter.highlight_text(text.clone());
+        let mut with_summary = without_summary.clone();
+        with_summary.push_line(summary_line);
+
+        let highlighter = highlighter.summary(true);
+        assert_eq!(highlighter.highlight_text(text), with_summary);
+        assert_eq!(
+            highlighter.highlight_text(String::from("nothing\nhere")),
+            Text::from(vec![
+                Line::from(vec![Span::from("nothing")]),
+                Line::from(vec![Span::from("here")]),
+            ])
+        );
+        assert_eq!(
+            highlighter.highlight_line(String::from("@a")),
+            Line::from(vec![Span::from("@a").style(STYLE)])
+        );
+    }
+
+    #[test]
+    fn rule_flags_test() {
+        let other = Style::new().fg(Color::Red);
+        let highlighter = MultiHighlighter::new([
+            Rule::new("url", r"https?://\S+", STYLE).flags(RegexFlags {
+                case_insensitive: true,
+                ..RegexFlags::default()
+            }),
+            Rule::new("todo", "TODO", other),
+        ])
+        .unwrap();
+
+        assert_eq!(highlighter.rules()[1].flags, RegexFlags::default());
+        assert_eq!(
+            highlighter.highlight_line(String::from("TODO todo http://a HTTP://B")),
+            Line::from(vec![
+                Span::from("TODO").style(other),
+                Span::from(" todo "),
+                Span::from("http://a").style(STYLE),
+                Span::from(" "),
+                Span::from("HTTP://B").style(STYLE),
+            ])
+        );
+    }
+}
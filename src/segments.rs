@@ -0,0 +1,347 @@
+use std::ops::Range;
+
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+/// A piece of a line, either matched or not, described by its byte range in the line.
+///
+/// Segments hold no text, so they can be computed once, filtered, merged or reordered freely,
+/// and turned back into a `Line` of any source with [`segments_to_line`] or
+/// [`segments_to_line_owned`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Segment {
+    /// The range of bytes of the line covered by the segment.
+    pub range: Range<usize>,
+    /// Whether the segment is a match, and gets the highlight style.
+    pub matched: bool,
+}
+
+/// Splits `line` into the matched and unmatched segments that
+/// [`highlight_line`](crate::highlight_line) would turn into `Spans`.
+///
+/// The segments are sorted and cover `line` without gaps or overlaps, so turning them back into
+/// a `Line` gives the same result as highlighting `line`. Zero-width matches are skipped.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be split.
+/// * `reg` - A compiled regular expression to match the text that needs to be highlighted.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{segment_line, Segment};
+/// use regex::Regex;
+///
+/// let reg = Regex::new(r"@\w+").unwrap();
+///
+/// assert_eq!(
+///     segment_line("Hi @buddy", &reg),
+///     vec![
+///         Segment { range: 0..3, matched: false },
+///         Segment { range: 3..9, matched: true },
+///     ]
+/// );
+/// ```
+#[cfg(feature = "regex")]
+pub fn segment_line(line: &str, reg: &Regex) -> Vec<Segment> {
+    fill_gaps(line.len(), reg.find_iter(line))
+        .map(|(range, m)| Segment {
+            range,
+            matched: m.is_some(),
+        })
+        .collect()
+}
+
+#[cfg(any(feature = "regex", feature = "literal"))]
+/// Something that covers a range of bytes of a line, like a match.
+pub(crate) trait Ranged {
+    fn range(&self) -> Range<usize>;
+}
+
+#[cfg(any(feature = "regex", feature = "literal"))]
+impl Ranged for Range<usize> {
+    fn range(&self) -> Range<usize> {
+        self.clone()
+    }
+}
+
+#[cfg(any(feature = "regex", feature = "literal"))]
+impl<T> Ranged for (Range<usize>, T) {
+    fn range(&self) -> Range<usize> {
+        self.0.clone()
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Ranged for regex::Match<'_> {
+    fn range(&self) -> Range<usize> {
+        regex::Match::range(self)
+    }
+}
+
+#[cfg(any(feature = "regex", feature = "literal"))]
+/// An iterator over the pieces of a line: the matches, and the gaps between them.
+///
+/// This is created by [`fill_gaps`].
+#[derive(Debug)]
+pub(crate) struct Gaps<I: Iterator> {
+    len: usize,
+    matches: I,
+    pending: Option<I::Item>,
+    last_index: usize,
+}
+
+#[cfg(any(feature = "regex", feature = "literal"))]
+/// Splits a line of `len` bytes into the given `matches` and the gaps between them, in order,
+/// yielding the range of every piece along with its match, or `None` for a gap.
+///
+/// The matches must be sorted and must not overlap. Empty matches are skipped, and so are
+/// empty gaps, so the ranges cover the line without gaps, overlaps or empty pieces.
+pub(crate) fn fill_gaps<I>(len: usize, matches: I) -> Gaps<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Ranged,
+{
+    Gaps {
+        len,
+        matches: matches.into_iter(),
+        pending: None,
+        last_index: 0,
+    }
+}
+
+#[cfg(any(feature = "regex", feature = "literal"))]
+impl<I: Iterator> Iterator for Gaps<I>
+where
+    I::Item: Ranged,
+{
+    type Item = (Range<usize>, Option<I::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let m = match self.pending.take() {
+            Some(m) => m,
+            None => match self.matches.find(|m| !m.range().is_empty()) {
+                Some(m) if m.range().start > self.last_index => {
+                    let gap = self.last_index..m.range().start;
+                    self.last_index = gap.end;
+                    self.pending = Some(m);
+                    return Some((gap, None));
+                }
+                Some(m) => m,
+                None if self.len > self.last_index => {
+                    let gap = self.last_index..self.len;
+                    self.last_index = gap.end;
+                    return Some((gap, None));
+                }
+                None => return None,
+            },
+        };
+
+        let range = m.range();
+        self.last_index = range.end;
+        Some((range, Some(m)))
+    }
+}
+
+#[cfg(any(feature = "regex", feature = "literal"))]
+/// Creates a `Line` out of `line`, styling the sorted, non-overlapping ranges of `matches`
+/// with the style each one comes with and leaving the gaps between them plain. The content of
+/// the `Spans` is copied, so the `Line` doesn't borrow from `line`.
+pub(crate) fn styled_line<'a>(
+    line: &str,
+    matches: impl IntoIterator<Item = (Range<usize>, Style)>,
+) -> Line<'a> {
+    fill_gaps(line.len(), matches)
+        .map(|(range, m)| {
+            let span = Span::from(line[range].to_string());
+            match m {
+                Some((_, style)) => span.style(style),
+                None => span,
+            }
+        })
+        .collect()
+}
+
+/// Creates a `Line` out of `segments` of `line`, applying `highlight_style` to the matched
+/// ones. The `Spans` borrow their content from `line`.
+///
+/// Every segment becomes one `Span`, in the given order, and empty segments are skipped. For
+/// the text of the returned `Line` to equal `line`, the segments must be sorted and cover it
+/// without gaps or overlaps, like the ones returned by [`segment_line`] do.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text the segments belong to.
+/// * `segments` - The segments to turn into `Spans`.
+/// * `highlight_style` - The style to be applied to the matched segments.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::{segments_to_line, Segment};
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let segments = [
+///     Segment { range: 0..3, matched: false },
+///     Segment { range: 3..9, matched: true },
+/// ];
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("Hi "),
+///     Span::from("@buddy").style(Style::new().bg(Color::Blue)),
+/// ]);
+///
+/// assert_eq!(
+///     segments_to_line("Hi @buddy", &segments, Style::new().bg(Color::Blue)),
+///     expected_line
+/// );
+/// ```
+///
+/// # Panics
+///
+/// The function panics if the range of a segment is out of the bounds of `line` or doesn't
+/// start and end at char boundaries.
+pub fn segments_to_line<'a>(
+    line: &'a str,
+    segments: &[Segment],
+    highlight_style: Style,
+) -> Line<'a> {
+    segments
+        .iter()
+        .filter(|segment| !segment.range.is_empty())
+        .map(|segment| {
+            let span = Span::from(&line[segment.range.clone()]);
+            if segment.matched {
+                span.style(highlight_style)
+            } else {
+                span
+            }
+        })
+        .collect()
+}
+
+/// Creates a `Line` like [`segments_to_line`], but copies the content of the `Spans`, so the
+/// `Line` doesn't borrow from `line`.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text the segments belong to.
+/// * `segments` - The segments to turn into `Spans`.
+/// * `highlight_style` - The style to be applied to the matched segments.
+///
+/// # Panics
+///
+/// The function panics if the range of a segment is out of the bounds of `line` or doesn't
+/// start and end at char boundaries.
+pub fn segments_to_line_owned(
+    line: &str,
+    segments: &[Segment],
+    highlight_style: Style,
+) -> Line<'static> {
+    segments
+        .iter()
+        .filter(|segment| !segment.range.is_empty())
+        .map(|segment| {
+            let span = Span::from(line[segment.range.clone()].to_string());
+            if segment.matched {
+                span.style(highlight_style)
+            } else {
+                span
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    const STYLE: Style = Style::new().bg(Color::Blue);
+
+    #[test]
+    fn segments_to_line_test() {
+        let line = "żółw @a b";
+        let segments = [
+            Segment {
+                range: 0..8,
+                matched: false,
+            },
+            Segment {
+                range: 8..8,
+                matched: true,
+            },
+            Segment {
+                range: 8..10,
+                matched: true,
+            },
+            Segment {
+                range: 10..12,
+                matched: false,
+            },
+        ];
+        let expected_line = Line::from(vec![
+            Span::from("żółw "),
+            Span::from("@a").style(STYLE),
+            Span::from(" b"),
+        ]);
+
+        assert_eq!(segments_to_line(line, &segments, STYLE), expected_line);
+        assert_eq!(
+            segments_to_line_owned(line, &segments, STYLE),
+            expected_line
+        );
+        assert_eq!(
+            segments_to_line(line, &segments[2..3], STYLE),
+            Line::from(vec![Span::from("@a").style(STYLE)])
+        );
+    }
+
+    #[cfg(any(feature = "regex", feature = "literal"))]
+    #[test]
+    fn fill_gaps_test() {
+        let matches = [(0..0, 'a'), (2..4, 'b'), (4..5, 'c'), (7..7, 'd')];
+
+        assert_eq!(
+            fill_gaps(8, matches).collect::<Vec<_>>(),
+            vec![
+                (0..2, None),
+                (2..4, Some((2..4, 'b'))),
+                (4..5, Some((4..5, 'c'))),
+                (5..8, None),
+            ]
+        );
+        assert_eq!(
+            fill_gaps(3, Some(0..3)).collect::<Vec<_>>(),
+            vec![(0..3, Some(0..3))]
+        );
+        assert_eq!(fill_gaps(0, Vec::<Range<usize>>::new()).count(), 0);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn segment_line_test() {
+        let reg = Regex::new(r"@\w+|\b").unwrap();
+
+        for line in ["", "@a", "żółw @b @c", "@a@b", "x @ y"] {
+            let segments = segment_line(line, &reg);
+
+            assert_eq!(
+                segments_to_line_owned(line, &segments, STYLE),
+                crate::highlight_line(line.to_string(), reg.as_str(), STYLE)
+            );
+            assert!(segments
+                .windows(2)
+                .all(|pair| pair[0].range.end == pair[1].range.start));
+            assert!(segments.iter().all(|segment| !segment.range.is_empty()));
+        }
+    }
+}
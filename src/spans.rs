@@ -0,0 +1,176 @@
+use std::borrow::Cow;
+
+use ratatui::{style::Style, text::Span};
+use regex::{Match, Matches, Regex};
+
+use crate::segments::{fill_gaps, Gaps};
+
+/// Splits `line` into plain and styled `Spans`, in order.
+///
+/// This is the building block the other highlighting functions are made of: collecting it into
+/// a `Line` gives the same result as [`highlight_line`](crate::highlight_line). It's also handy
+/// for building your own line types or for interleaving custom `Spans` with the highlighted ones.
+///
+/// Zero-width matches have nothing to style and are skipped. The returned `Spans` borrow their
+/// content from `line`, so they live as long as `line` does, regardless of how long `reg` is
+/// kept around.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the line of text to be highlighted.
+/// * `reg` - A compiled regular expression to match the text that needs to be highlighted.
+/// * `highlight_style` - The style to be applied to the matching text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::styled_spans;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+/// use regex::Regex;
+///
+/// let reg = Regex::new(r"@\w+").unwrap();
+/// let highlight_style = Style::new().bg(Color::Blue);
+///
+/// let mut spans = vec![Span::from("> ")];
+/// spans.extend(styled_spans("Hi @buddy", &reg, highlight_style));
+///
+/// let expected_line = Line::from(vec![
+///     Span::from("> "),
+///     Span::from("Hi "),
+///     Span::from("@buddy").style(Style::new().bg(Color::Blue)),
+/// ]);
+///
+/// assert_eq!(Line::from(spans), expected_line);
+/// ```
+pub fn styled_spans<'r, 'h>(
+    line: &'h str,
+    reg: &'r Regex,
+    highlight_style: Style,
+) -> StyledSpans<'r, 'h> {
+    StyledSpans::new(line, reg, highlight_style, Style::default())
+}
+
+/// An iterator over the plain and styled `Spans` of a line.
+///
+/// This is created by [`styled_spans`].
+#[derive(Debug)]
+pub struct StyledSpans<'r, 'h>(MatchSpans<'h, Matches<'r, 'h>>);
+
+impl<'r, 'h> StyledSpans<'r, 'h> {
+    pub(crate) fn new(
+        line: &'h str,
+        reg: &'r Regex,
+        highlight_style: Style,
+        rest_style: Style,
+    ) -> Self {
+        Self(MatchSpans::new(
+            line,
+            reg.find_iter(line),
+            highlight_style,
+            rest_style,
+        ))
+    }
+}
+
+impl<'h> Iterator for StyledSpans<'_, 'h> {
+    type Item = Span<'h>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+/// The iterator behind [`StyledSpans`], styling any sorted, non-overlapping `matches` of `line`
+/// rather than only the ones of a `Regex`, e.g. after filtering some of them out.
+#[derive(Debug)]
+pub(crate) struct MatchSpans<'h, I: Iterator<Item = Match<'h>>> {
+    line: &'h str,
+    pieces: Gaps<I>,
+    highlight_style: Style,
+    rest_style: Style,
+    matched: bool,
+}
+
+impl<'h, I: Iterator<Item = Match<'h>>> MatchSpans<'h, I> {
+    pub(crate) fn new(
+        line: &'h str,
+        matches: I,
+        highlight_style: Style,
+        rest_style: Style,
+    ) -> Self {
+        Self {
+            line,
+            pieces: fill_gaps(line.len(), matches),
+            highlight_style,
+            rest_style,
+            matched: false,
+        }
+    }
+
+    /// Returns whether a styled `Span` was yielded so far.
+    pub(crate) fn matched(&self) -> bool {
+        self.matched
+    }
+}
+
+impl<'h, I: Iterator<Item = Match<'h>>> Iterator for MatchSpans<'h, I> {
+    type Item = Span<'h>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (range, m) = self.pieces.next()?;
+        let span = Span::from(&self.line[range]);
+
+        Some(match m {
+            Some(_) => {
+                self.matched = true;
+                span.style(self.highlight_style)
+            }
+            None => span.style(self.rest_style),
+        })
+    }
+}
+
+/// Copies the content of `span`, so it doesn't borrow anything anymore. Everything else is
+/// moved over from `span`.
+pub(crate) fn into_owned(span: Span<'_>) -> Span<'static> {
+    Span {
+        content: Cow::Owned(span.content.into_owned()),
+        ..span
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    const STYLE: Style = Style::new().bg(Color::Blue);
+
+    #[test]
+    fn styled_spans_test() {
+        let reg = Regex::new(r"@\w+").unwrap();
+
+        assert_eq!(
+            styled_spans("@a b@c@d e", &reg, STYLE).collect::<Vec<_>>(),
+            vec![
+                Span::from("@a").style(STYLE),
+                Span::from(" b"),
+                Span::from("@c").style(STYLE),
+                Span::from("@d").style(STYLE),
+                Span::from(" e"),
+            ]
+        );
+        assert_eq!(styled_spans("", &reg, STYLE).count(), 0);
+        assert_eq!(
+            styled_spans("aab", &Regex::new(r"b*").unwrap(), STYLE).collect::<Vec<_>>(),
+            vec![Span::from("aa"), Span::from("b").style(STYLE)]
+        );
+        assert_eq!(
+            styled_spans("no match", &reg, STYLE).collect::<Vec<_>>(),
+            vec![Span::from("no match")]
+        );
+    }
+}
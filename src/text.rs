@@ -0,0 +1,500 @@
+use ratatui::{
+    style::Style,
+    text::{Line, Span, Text},
+};
+
+#[cfg(feature = "regex")]
+use crate::highlight_line;
+
+/// Concatenates the content of all `Spans` of `line`, dropping their styles.
+///
+/// For a `Line` created by [`highlight_line`](crate::highlight_line) this gives back the exact
+/// line that was highlighted. Functions that transform the text, like
+/// [`highlight_bytes`](crate::highlight_bytes) replacing control characters, or keeping line
+/// endings as `Spans`, are lossy, and what comes back is the transformed text.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::line_to_string;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span},
+/// };
+///
+/// let line = Line::from(vec![
+///     Span::from("Hi "),
+///     Span::from("@buddy").style(Style::new().bg(Color::Blue)),
+/// ]);
+/// assert_eq!(line_to_string(&line), "Hi @buddy");
+/// ```
+pub fn line_to_string(line: &Line<'_>) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+/// Concatenates the content of all `Lines` of `text` with `'\n'`, dropping their styles.
+///
+/// For `Text` created by [`highlight_text`](crate::highlight_text) this gives back the
+/// highlighted text, except for a trailing `'\n'` which doesn't produce a `Line` of its own
+/// and is lost. The same caveats as for [`line_to_string`] apply.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::text_to_string;
+/// use ratatui::text::{Line, Text};
+///
+/// let text = Text::from(vec![Line::from("Hi @buddy"), Line::default(), Line::from("@stranger")]);
+/// assert_eq!(text_to_string(&text), "Hi @buddy\n\n@stranger");
+/// ```
+pub fn text_to_string(text: &Text<'_>) -> String {
+    text.lines
+        .iter()
+        .map(line_to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Checks that highlighting `line` with `pattern` leaves its text intact, that is, that the
+/// content of the `Spans` returned by [`highlight_line`] adds up to exactly `line`.
+///
+/// This is the invariant every highlighting function relies on, exposed so you can fuzz your
+/// own patterns with it.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::preserves_text;
+///
+/// assert!(preserves_text("żółw @buddy", r"\b|@\w*"));
+/// ```
+///
+/// # Panics
+///
+/// The function may panic if the provided pattern is an invalid regular expression.
+#[cfg(feature = "regex")]
+pub fn preserves_text(line: &str, pattern: impl AsRef<str>) -> bool {
+    line_to_string(&highlight_line(line.to_string(), pattern, Style::new())) == line
+}
+
+/// Patches `even` onto the style of every even `Line` of `text`, and `odd` onto every odd one,
+/// counting from zero.
+///
+/// The styles are patched onto the `Lines` rather than their `Spans`, so highlighted `Spans`
+/// keep their own style and are still drawn on top of the stripes.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::with_zebra;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = Text::from(vec![
+///     Line::from("a"),
+///     Line::from(vec![Span::from("b").style(Style::new().fg(Color::Red))]),
+///     Line::from("c"),
+/// ]);
+/// let striped = with_zebra(text, Style::new().bg(Color::Black), Style::new().bg(Color::DarkGray));
+///
+/// assert_eq!(striped.lines[0].style, Style::new().bg(Color::Black));
+/// assert_eq!(striped.lines[1].style, Style::new().bg(Color::DarkGray));
+/// assert_eq!(striped.lines[1].spans[0].style, Style::new().fg(Color::Red));
+/// assert_eq!(striped.lines[2].style, Style::new().bg(Color::Black));
+/// ```
+pub fn with_zebra(mut text: Text<'_>, even: Style, odd: Style) -> Text<'_> {
+    for (i, line) in text.lines.iter_mut().enumerate() {
+        let stripe = if i % 2 == 0 { even } else { odd };
+        line.style = line.style.patch(stripe);
+    }
+
+    text
+}
+
+/// Collapses every run of consecutive `Lines` with identical plain text into its first `Line`,
+/// followed by a `(×N)` suffix styled with `repeat_style`, where `N` is the length of the run.
+///
+/// `Lines` are compared by their text only (see [`line_to_string`]), so the kept `Line` is the
+/// first of the run with its styles intact. The intended order is to highlight first and
+/// collapse afterwards, so the collapsed `Lines` keep their highlights.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::collapse_duplicates;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let text = Text::from(vec![Line::from("ping"), Line::from("ping"), Line::from("pong")]);
+/// let repeat_style = Style::new().fg(Color::DarkGray);
+///
+/// let expected_text = Text::from(vec![
+///     Line::from(vec![
+///         Span::from("ping"),
+///         Span::from(" "),
+///         Span::from("(×2)").style(Style::new().fg(Color::DarkGray)),
+///     ]),
+///     Line::from("pong"),
+/// ]);
+///
+/// assert_eq!(collapse_duplicates(text, repeat_style), expected_text);
+/// ```
+pub fn collapse_duplicates(text: Text<'_>, repeat_style: Style) -> Text<'_> {
+    let mut runs: Vec<(Line<'_>, String, usize)> = Vec::new();
+
+    for line in text.lines {
+        let plain = line_to_string(&line);
+        match runs.last_mut() {
+            Some((_, last, count)) if *last == plain => *count += 1,
+            _ => runs.push((line, plain, 1)),
+        }
+    }
+
+    let lines = runs.into_iter().map(|(mut line, _, count)| {
+        if count > 1 {
+            line.push_span(Span::from(" "));
+            line.push_span(Span::from(format!("(×{count})")).style(repeat_style));
+        }
+        line
+    });
+
+    Text {
+        lines: lines.collect(),
+        ..text
+    }
+}
+
+/// Combines two independently highlighted versions of the same text by patching the styles of
+/// `overlay` on top of the ones of `base`, `Line` by `Line` and character by character.
+///
+/// This is for highlighting passes that can't be chained through one highlighter, like a syntax
+/// pass and a search pass computed separately. The `Spans` of the result are cut wherever a
+/// `Span` of either text ends, the content comes from `base`, and each piece gets the style of
+/// its `base` `Span` patched with the style of its `overlay` `Span`, so unstyled parts of
+/// `overlay` leave `base` as it is. `Line` and `Text` styles are patched the same way, the
+/// alignment of `base` wins over the one of `overlay`, and empty `Spans` are dropped.
+///
+/// Both texts must have the same plain content (see [`line_to_string`]) on the same number of
+/// `Lines`; otherwise `None` is returned.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::merge_text;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let syntax = Text::from(vec![Line::from(vec![
+///     Span::from("let").style(Style::new().fg(Color::Red)),
+///     Span::from(" x = 1"),
+/// ])]);
+/// let search = Text::from(vec![Line::from(vec![
+///     Span::from("le"),
+///     Span::from("t x").style(Style::new().bg(Color::Blue)),
+///     Span::from(" = 1"),
+/// ])]);
+///
+/// let expected_text = Text::from(vec![Line::from(vec![
+///     Span::from("le").style(Style::new().fg(Color::Red)),
+///     Span::from("t").style(Style::new().fg(Color::Red).bg(Color::Blue)),
+///     Span::from(" x").style(Style::new().bg(Color::Blue)),
+///     Span::from(" = 1"),
+/// ])]);
+///
+/// assert_eq!(merge_text(syntax, search), Some(expected_text));
+/// assert_eq!(merge_text(Text::from("a"), Text::from("b")), None);
+/// ```
+pub fn merge_text<'a>(base: Text<'a>, overlay: Text<'_>) -> Option<Text<'a>> {
+    if base.lines.len() != overlay.lines.len() {
+        return None;
+    }
+
+    let lines = base
+        .lines
+        .into_iter()
+        .zip(&overlay.lines)
+        .map(|(base, overlay)| merge_line(base, overlay))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Text {
+        lines,
+        style: base.style.patch(overlay.style),
+        alignment: base.alignment.or(overlay.alignment),
+    })
+}
+
+fn merge_line<'a>(base: Line<'a>, overlay: &Line<'_>) -> Option<Line<'a>> {
+    if line_to_string(&base) != line_to_string(overlay) {
+        return None;
+    }
+
+    let mut overlay_spans = overlay
+        .spans
+        .iter()
+        .map(|span| (span.content.as_ref(), span.style));
+    let mut current = ("", Style::new());
+    let mut spans = Vec::new();
+
+    for span in &base.spans {
+        let mut rest = span.content.as_ref();
+
+        while !rest.is_empty() {
+            while current.0.is_empty() {
+                current = overlay_spans.next()?;
+            }
+            // both lines have the same text, so the end of either span is a char boundary
+            let len = rest.len().min(current.0.len());
+            spans.push(Span::from(rest[..len].to_string()).style(span.style.patch(current.1)));
+            rest = &rest[len..];
+            current.0 = &current.0[len..];
+        }
+    }
+
+    Some(Line {
+        spans,
+        style: base.style.patch(overlay.style),
+        alignment: base.alignment.or(overlay.alignment),
+    })
+}
+
+/// Checks whether `a` and `b` render the same, regardless of how their content is split into
+/// `Spans`.
+///
+/// Two texts are visually equal when they have the same number of `Lines`, every pair of
+/// `Lines` has the same alignment, and the same chars with the same styles in the same order.
+/// The style of a char is the style of its `Span` patched onto the style of its `Line`,
+/// itself patched onto the style of the `Text`, which is what ends up in the terminal; the
+/// alignment of a `Line` falls back to the one of the `Text` the same way.
+///
+/// This is meant for tests: `assert_eq!` on `Text` also compares the `Span` boundaries, which
+/// differ between results that look the same, e.g. before and after merging adjacent `Spans`.
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::text_visually_eq;
+/// use ratatui::{
+///     style::{Color, Style},
+///     text::{Line, Span, Text},
+/// };
+///
+/// let style = Style::new().bg(Color::Blue);
+/// let a = Text::from(vec![Line::from(vec![Span::from("@a").style(style), Span::from("@b").style(style)])]);
+/// let b = Text::from(vec![Line::from(vec![Span::from("@a@b").style(style)])]);
+///
+/// assert!(text_visually_eq(&a, &b));
+/// assert!(!text_visually_eq(&a, &Text::from("@a@b")));
+/// ```
+pub fn text_visually_eq(a: &Text<'_>, b: &Text<'_>) -> bool {
+    a.lines.len() == b.lines.len()
+        && a.lines.iter().zip(&b.lines).all(|(line_a, line_b)| {
+            line_a.alignment.or(a.alignment) == line_b.alignment.or(b.alignment)
+                && styled_chars(a, line_a).eq(styled_chars(b, line_b))
+        })
+}
+
+fn styled_chars<'t>(
+    text: &'t Text<'_>,
+    line: &'t Line<'_>,
+) -> impl Iterator<Item = (char, Style)> + 't {
+    let line_style = text.style.patch(line.style);
+
+    line.spans.iter().flat_map(move |span| {
+        let style = line_style.patch(span.style);
+        span.content.chars().map(move |c| (c, style))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Color, Modifier};
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn to_string_test() {
+        use crate::highlight_text;
+
+        let text = highlight_text(String::from("a @b c\n\n@d\n"), r"@\w+", Style::new());
+
+        assert_eq!(line_to_string(&text.lines[0]), "a @b c");
+        assert_eq!(line_to_string(&Line::default()), "");
+        assert_eq!(text_to_string(&text), "a @b c\n\n@d");
+        assert_eq!(text_to_string(&Text::default()), "");
+    }
+
+    #[cfg(feature = "regex")]
+    proptest::proptest! {
+        #[test]
+        fn preserves_text_test(line in "\\PC{0,40}", pattern in r"[aé.*+?|()\\bw^$]{0,8}") {
+            proptest::prop_assume!(regex::Regex::new(&pattern).is_ok());
+            proptest::prop_assert!(preserves_text(&line, &pattern));
+        }
+    }
+
+    #[test]
+    fn collapse_duplicates_test() {
+        let style = Style::new().fg(Color::Red);
+        let repeat_style = Style::new().fg(Color::DarkGray);
+        let text = Text::from(vec![
+            Line::from(vec![Span::from("a "), Span::from("b").style(style)]),
+            Line::from("a b"),
+            Line::from("a b"),
+            Line::from("c"),
+            Line::default(),
+            Line::default(),
+            Line::from("a b"),
+        ]);
+
+        assert_eq!(
+            collapse_duplicates(text, repeat_style),
+            Text::from(vec![
+                Line::from(vec![
+                    Span::from("a "),
+                    Span::from("b").style(style),
+                    Span::from(" "),
+                    Span::from("(×3)").style(repeat_style),
+                ]),
+                Line::from("c"),
+                Line::from(vec![
+                    Span::from(" "),
+                    Span::from("(×2)").style(repeat_style),
+                ]),
+                Line::from("a b"),
+            ])
+        );
+        assert_eq!(
+            collapse_duplicates(Text::default(), repeat_style),
+            Text::default()
+        );
+    }
+
+    #[test]
+    fn with_zebra_test() {
+        let even = Style::new().bg(Color::Black);
+        let odd = Style::new().bg(Color::DarkGray);
+        let text = Text::from(vec![
+            Line::from("a").style(Style::new().add_modifier(Modifier::BOLD)),
+            Line::from("b").style(Style::new().fg(Color::Red).bg(Color::Blue)),
+        ]);
+
+        let striped = with_zebra(text, even, odd);
+        assert_eq!(
+            striped.lines[0].style,
+            Style::new().bg(Color::Black).add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(
+            striped.lines[1].style,
+            Style::new().fg(Color::Red).bg(Color::DarkGray)
+        );
+
+        assert_eq!(with_zebra(Text::default(), even, odd), Text::default());
+    }
+
+    #[test]
+    fn merge_text_test() {
+        let red = Style::new().fg(Color::Red);
+        let blue = Style::new().bg(Color::Blue);
+        let base = Text::from(vec![
+            Line::from(vec![
+                Span::from("żó").style(red),
+                Span::from(""),
+                Span::from("łw a"),
+            ]),
+            Line::default(),
+            Line::from("b c").style(red),
+        ]);
+        let overlay = Text::from(vec![
+            Line::from(vec![
+                Span::from("ż"),
+                Span::from("ółw").style(blue),
+                Span::from(" a"),
+            ]),
+            Line::default(),
+            Line::from(vec![Span::from("b "), Span::from("c").style(blue)])
+                .style(blue)
+                .centered(),
+        ]);
+
+        assert_eq!(
+            merge_text(base.clone(), overlay),
+            Some(Text::from(vec![
+                Line::from(vec![
+                    Span::from("ż").style(red),
+                    Span::from("ó").style(red.patch(blue)),
+                    Span::from("łw").style(blue),
+                    Span::from(" a"),
+                ]),
+                Line::default(),
+                Line::from(vec![Span::from("b "), Span::from("c").style(blue)])
+                    .style(red.patch(blue))
+                    .centered(),
+            ]))
+        );
+        assert_eq!(
+            merge_text(base.clone(), Text::from("żółw a\n\nb c")),
+            Some(Text::from(vec![
+                Line::from(vec![Span::from("żó").style(red), Span::from("łw a")]),
+                Line::default(),
+                Line::from(vec![Span::from("b c")]).style(red),
+            ]))
+        );
+        assert_eq!(merge_text(base.clone(), Text::from("żółw a\n\nb")), None);
+        assert_eq!(merge_text(base, Text::from("żółw a\n")), None);
+    }
+
+    #[test]
+    fn text_visually_eq_test() {
+        let red = Style::new().fg(Color::Red);
+        let blue = Style::new().bg(Color::Blue);
+        let text = Text::from(vec![
+            Line::from(vec![
+                Span::from("a"),
+                Span::from("bc").style(blue),
+                Span::from(""),
+            ]),
+            Line::from(vec![Span::from("d").style(red)]).centered(),
+        ]);
+
+        let same = Text::from(vec![
+            Line::from(vec![
+                Span::from("a"),
+                Span::from("b").style(blue),
+                Span::from("c").style(blue),
+            ]),
+            Line::from("d").style(red).centered(),
+        ]);
+        assert!(text_visually_eq(&text, &text));
+        assert!(text_visually_eq(&text, &same));
+        assert!(text_visually_eq(
+            &Text::from("x").style(red).centered(),
+            &Text::from(vec![Line::from(vec![Span::from("x").style(red)]).centered()])
+        ));
+
+        let different = [
+            Text::from("abc\nd"),
+            Text::from(vec![text.lines[0].clone()]),
+            Text::from(vec![text.lines[0].clone(), Line::from("d").style(red)]),
+            Text::from(vec![
+                text.lines[0].clone(),
+                Line::from("e").style(red).centered(),
+            ]),
+            Text::from(vec![
+                text.lines[0].clone(),
+                Line::from("d").style(red.bg(Color::Reset)).centered(),
+            ]),
+        ];
+        for different in different {
+            assert!(!text_visually_eq(&text, &different));
+        }
+    }
+}
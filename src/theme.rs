@@ -0,0 +1,417 @@
+#[cfg(feature = "serde")]
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::{MultiHighlighter, Rule, RuleError, PATH_PATTERN, URL_PATTERN};
+
+/// The pattern of the `mention` rule of the preset themes.
+const MENTION_PATTERN: &str = r"@\w+";
+/// The pattern of the `tag` rule of the preset themes.
+const TAG_PATTERN: &str = r"#\w+";
+
+/// A set of styled [`Rules`](Rule), ready to be compiled into a [`MultiHighlighter`].
+///
+/// The presets style the same builtin patterns and only differ in their colors, so switching
+/// between them never changes what gets highlighted. Their rules are, from the highest priority
+/// to the lowest:
+///
+/// * `url` - [`URL_PATTERN`],
+/// * `path` - [`PATH_PATTERN`],
+/// * `mention` - `@` followed by a word, like `@buddy`,
+/// * `tag` - `#` followed by a word, like `#rust`.
+///
+/// The patterns are used as they are, so the extra checks done by
+/// [`highlight_urls`](crate::highlight_urls) and [`highlight_paths`](crate::highlight_paths),
+/// like trimming the punctuation ending a sentence, don't apply. The default theme is
+/// [`solarized_search`](Self::solarized_search).
+///
+/// # Example
+///
+/// ```
+/// use tui_pattern_highlighter::Theme;
+///
+/// let highlighter = Theme::high_contrast().highlighter().unwrap();
+/// let line = highlighter.highlight_line(String::from("@buddy see https://x.org #rust"));
+///
+/// assert_eq!(line.spans.len(), 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Theme {
+    /// The rules of the theme, in the order of their priority.
+    pub rules: Vec<Rule>,
+}
+
+impl Theme {
+    /// Creates a new `Theme` out of `rules`.
+    pub fn new(rules: impl IntoIterator<Item = Rule>) -> Self {
+        Self {
+            rules: rules.into_iter().collect(),
+        }
+    }
+
+    /// A theme using the accent colors of the Solarized palette, as RGB colors. It reads well
+    /// on both the light and the dark Solarized backgrounds.
+    pub fn solarized_search() -> Self {
+        Self::builtin([
+            Style::new()
+                .fg(Color::Rgb(0x26, 0x8b, 0xd2))
+                .add_modifier(Modifier::UNDERLINED),
+            Style::new().fg(Color::Rgb(0x2a, 0xa1, 0x98)),
+            Style::new()
+                .fg(Color::Rgb(0xd3, 0x36, 0x82))
+                .add_modifier(Modifier::BOLD),
+            Style::new().fg(Color::Rgb(0x85, 0x99, 0x00)),
+        ])
+    }
+
+    /// A theme painting black text on bright backgrounds, using only the 16 basic colors, so it
+    /// stands out on any background and in any terminal.
+    pub fn high_contrast() -> Self {
+        let on = |bg| Style::new().fg(Color::Black).bg(bg);
+
+        Self::builtin([
+            on(Color::LightCyan).add_modifier(Modifier::UNDERLINED),
+            on(Color::LightGreen),
+            on(Color::Yellow).add_modifier(Modifier::BOLD),
+            on(Color::LightMagenta),
+        ])
+    }
+
+    /// Compiles the rules of the theme into a new [`MultiHighlighter`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first rule whose pattern is an invalid regular expression,
+    /// which never happens for the presets.
+    pub fn highlighter(&self) -> Result<MultiHighlighter, RuleError> {
+        MultiHighlighter::new(self.rules.iter().cloned())
+    }
+
+    /// Reads every `.toml` file in the directory at `path` into a theme named after the file,
+    /// and compiles it, e.g. to let users pick a highlight profile at runtime. Other files and
+    /// subdirectories are skipped. Requires the `serde` feature.
+    ///
+    /// A theme file is a [`Theme`] in TOML, with its rules in order of priority. Styles are in
+    /// the format of ratatui, with the colors in any format [`Color`] can be parsed from and
+    /// the modifiers named like the [`Modifier`] constants. The `flags` of a rule, and any of
+    /// the [`RegexFlags`](crate::RegexFlags) in it, can be left out to use the defaults.
+    ///
+    /// ```toml
+    /// # ~/.config/app/themes/calm.toml
+    /// [[rules]]
+    /// label = "url"
+    /// pattern = 'https?://\S+'
+    /// style = { fg = "blue", add_modifier = "UNDERLINED", sub_modifier = "" }
+    ///
+    /// [[rules]]
+    /// label = "mention"
+    /// pattern = '@\w+'
+    /// style = { fg = "#d33682", bg = "black", add_modifier = "BOLD | ITALIC", sub_modifier = "" }
+    /// flags = { case_insensitive = true }
+    /// ```
+    ///
+    /// Every theme is compiled while loading, so the themes are known to be valid, but the
+    /// compiled form is dropped: use [`load_dir_compiled`](Self::load_dir_compiled) to keep it
+    /// instead of compiling the themes again with [`highlighter`](Self::highlighter).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or one of the theme files can't be read, if a theme
+    /// file is malformed, or if a pattern is an invalid regular expression. The error holds the
+    /// path of the failing file.
+    #[cfg(feature = "serde")]
+    pub fn load_dir(path: impl AsRef<Path>) -> Result<HashMap<String, Theme>, ThemeError> {
+        Ok(Self::load_entries(path.as_ref())?
+            .into_iter()
+            .map(|(name, theme, _)| (name, theme))
+            .collect())
+    }
+
+    /// Reads the themes in the directory at `path` like [`load_dir`](Self::load_dir), and
+    /// returns them already compiled into [`MultiHighlighters`](MultiHighlighter). Requires the
+    /// `serde` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`load_dir`](Self::load_dir).
+    #[cfg(feature = "serde")]
+    pub fn load_dir_compiled(
+        path: impl AsRef<Path>,
+    ) -> Result<HashMap<String, MultiHighlighter>, ThemeError> {
+        Ok(Self::load_entries(path.as_ref())?
+            .into_iter()
+            .map(|(name, _, highlighter)| (name, highlighter))
+            .collect())
+    }
+
+    /// Reads and compiles every theme file in the directory at `path`, in the order of their
+    /// paths.
+    #[cfg(feature = "serde")]
+    fn load_entries(path: &Path) -> Result<Vec<(String, Theme, MultiHighlighter)>, ThemeError> {
+        let io_error = |source| ThemeError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        let mut paths = fs::read_dir(path)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<io::Result<Vec<_>>>()
+            })
+            .map_err(io_error)?;
+        // the order of the entries is platform-dependent, and the error about the first broken
+        // file should be the same everywhere
+        paths.sort();
+
+        let mut entries = Vec::new();
+        for path in paths {
+            let Some(name) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .filter(|_| path.extension().is_some_and(|ext| ext == "toml"))
+                .filter(|_| path.is_file())
+            else {
+                continue;
+            };
+
+            let config = fs::read_to_string(&path).map_err(|source| ThemeError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            let theme = toml::from_str::<Theme>(&config).map_err(|source| ThemeError::Parse {
+                path: path.clone(),
+                source,
+            })?;
+            let highlighter = theme.highlighter().map_err(|source| ThemeError::Rule {
+                path: path.clone(),
+                source,
+            })?;
+
+            entries.push((name.to_string(), theme, highlighter));
+        }
+
+        Ok(entries)
+    }
+
+    fn builtin([url, path, mention, tag]: [Style; 4]) -> Self {
+        Self::new([
+            Rule::new("url", URL_PATTERN, url),
+            Rule::new("path", PATH_PATTERN, path),
+            Rule::new("mention", MENTION_PATTERN, mention),
+            Rule::new("tag", TAG_PATTERN, tag),
+        ])
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::solarized_search()
+    }
+}
+
+/// The error returned by [`Theme::load_dir`], naming the file that failed to load.
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum ThemeError {
+    /// The directory or a theme file couldn't be read.
+    Io {
+        /// The path of the directory or of the file.
+        path: PathBuf,
+        /// The error returned while reading it.
+        source: io::Error,
+    },
+    /// A theme file isn't a valid theme in TOML.
+    Parse {
+        /// The path of the file.
+        path: PathBuf,
+        /// The error returned by `toml`, pointing at what is wrong in the file.
+        source: toml::de::Error,
+    },
+    /// The pattern of a rule in a theme file is an invalid regular expression.
+    Rule {
+        /// The path of the file.
+        path: PathBuf,
+        /// The error naming the rule.
+        source: RuleError,
+    },
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "failed to read `{}`: {source}", path.display()),
+            Self::Parse { path, source } => write!(f, "{}: {source}", path.display()),
+            Self::Rule { path, source } => write!(f, "{}: {source}", path.display()),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Error for ThemeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Parse { source, .. } => Some(source),
+            Self::Rule { source, .. } => Some(source),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::text::{Line, Span};
+
+    #[test]
+    fn presets_test() {
+        let line = "@a opened https://x.org/b in src/lib.rs #c";
+
+        for theme in [Theme::solarized_search(), Theme::high_contrast()] {
+            let highlighter = theme.highlighter().unwrap();
+            let style = |label| {
+                theme
+                    .rules
+                    .iter()
+                    .find(|rule| rule.label == label)
+                    .unwrap()
+                    .style
+            };
+
+            assert!(theme
+                .rules
+                .iter()
+                .all(|rule| rule.style != Style::default()));
+            assert_eq!(
+                highlighter.highlight_line(line.to_string()),
+                Line::from(vec![
+                    Span::from("@a").style(style("mention")),
+                    Span::from(" opened "),
+                    Span::from("https://x.org/b").style(style("url")),
+                    Span::from(" in "),
+                    Span::from("src/lib.rs").style(style("path")),
+                    Span::from(" "),
+                    Span::from("#c").style(style("tag")),
+                ])
+            );
+        }
+
+        assert_eq!(Theme::default(), Theme::solarized_search());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        for theme in [Theme::solarized_search(), Theme::high_contrast()] {
+            let config = toml::to_string(&theme).unwrap();
+            assert_eq!(toml::from_str::<Theme>(&config), Ok(theme));
+        }
+
+        let config = r##"
+            [[rules]]
+            label = "url"
+            pattern = 'https?://\S+'
+            style = { fg = "blue", add_modifier = "BOLD | UNDERLINED", sub_modifier = "" }
+            flags = { case_insensitive = true }
+
+            [[rules]]
+            label = "mention"
+            pattern = '@\w+'
+            style = { bg = "#102030", add_modifier = "", sub_modifier = "" }
+        "##;
+
+        assert_eq!(
+            toml::from_str::<Theme>(config),
+            Ok(Theme::new([
+                Rule::new(
+                    "url",
+                    r"https?://\S+",
+                    Style::new()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+                )
+                .flags(crate::RegexFlags {
+                    case_insensitive: true,
+                    ..crate::RegexFlags::default()
+                }),
+                Rule::new(
+                    "mention",
+                    r"@\w+",
+                    Style::new().bg(Color::Rgb(0x10, 0x20, 0x30))
+                ),
+            ]))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "tui-pattern-highlighter-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn load_dir_test() {
+        let tag = r"
+            [[rules]]
+            label = 'tag'
+            pattern = '#\w+'
+            style = { fg = 'green', add_modifier = '', sub_modifier = '' }
+        ";
+
+        let dir = temp_dir("load-dir");
+        fs::write(dir.join("calm.toml"), tag).unwrap();
+        fs::write(dir.join("empty.toml"), "rules = []").unwrap();
+        fs::write(dir.join("notes.txt"), "rules = 1").unwrap();
+        fs::create_dir(dir.join("nested.toml")).unwrap();
+
+        let themes = Theme::load_dir(&dir).unwrap();
+        assert_eq!(themes.len(), 2);
+        assert_eq!(
+            themes["calm"],
+            Theme::new([Rule::new("tag", r"#\w+", Style::new().fg(Color::Green))])
+        );
+        assert_eq!(themes["empty"], Theme::new([]));
+
+        let highlighters = Theme::load_dir_compiled(&dir).unwrap();
+        assert_eq!(highlighters.len(), 2);
+        assert_eq!(highlighters["calm"].rules(), themes["calm"].rules);
+        assert!(highlighters["empty"].rules().is_empty());
+
+        fs::write(dir.join("broken.toml"), tag.replace(r"#\w+", "(")).unwrap();
+        let err = Theme::load_dir(&dir).unwrap_err();
+        assert!(matches!(
+            &err,
+            ThemeError::Rule { path, source } if path.ends_with("broken.toml") && source.label == "tag"
+        ));
+        assert!(err.to_string().contains("invalid pattern for rule `tag`"));
+        assert!(Theme::load_dir_compiled(&dir).is_err());
+
+        fs::write(dir.join("broken.toml"), tag.replace("green", "nope")).unwrap();
+        let err = Theme::load_dir(&dir).unwrap_err();
+        assert!(matches!(
+            &err,
+            ThemeError::Parse { path, .. } if path.ends_with("broken.toml")
+        ));
+        assert!(err.source().is_some());
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(
+            Theme::load_dir(&dir),
+            Err(ThemeError::Io { path, .. }) if path == dir
+        ));
+    }
+}